@@ -0,0 +1,95 @@
+use crate::{Error, IS31FL3731, LED_COUNT};
+use embedded_hal::i2c::I2c;
+
+/// An effect drawn one frame at a time. Keeping the effect apart from the bus means it can be
+/// tried out without hardware by calling [render](Self::render) directly.
+pub trait Animation {
+    /// Draw frame number `frame` into `buf`. The buffer holds whatever was drawn for the
+    /// previous frame, so effects can either redraw it completely or build on it (e.g. fading
+    /// trails).
+    fn render(&mut self, frame: u64, buf: &mut [u8; LED_COUNT]);
+}
+
+/// Plays an [Animation] on the current frame of a device, one frame for every call to
+/// [tick](Self::tick_blocking). Call `tick` at a steady rate to set the speed of the animation.
+pub struct Animator<I2C, A> {
+    pub device: IS31FL3731<I2C>,
+    pub animation: A,
+    frame: u64,
+    buf: [u8; LED_COUNT],
+}
+
+impl<I2C, A: Animation> Animator<I2C, A> {
+    /// Wrap an already setup device, starting the animation at frame 0 on a dark buffer.
+    pub fn new(device: IS31FL3731<I2C>, animation: A) -> Self {
+        Self {
+            device,
+            animation,
+            frame: 0,
+            buf: [0; LED_COUNT],
+        }
+    }
+
+    /// The number of the frame the next [tick](Self::tick_blocking) will render.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Start the animation again from frame 0. The buffer is left as it is.
+    pub fn restart(&mut self) {
+        self.frame = 0;
+    }
+}
+
+impl<I2C, I2cError, A> Animator<I2C, A>
+where
+    I2C: I2c<Error = I2cError>,
+    A: Animation,
+{
+    /// Render the next frame of the animation and write it to the current frame in a single
+    /// write.
+    pub fn tick_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        self.animation.render(self.frame, &mut self.buf);
+        self.frame += 1;
+        self.device.all_pixels_blocking(&self.buf)
+    }
+}
+
+/// Every LED fading up and down together between 0 and `brightness`, taking `period` frames for
+/// each pulse.
+#[cfg(feature = "animations")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pulse {
+    pub brightness: u8,
+    pub period: u16,
+}
+
+#[cfg(feature = "animations")]
+impl Animation for Pulse {
+    fn render(&mut self, frame: u64, buf: &mut [u8; LED_COUNT]) {
+        let period = self.period.max(2) as u64;
+        let half = period / 2;
+        let step = frame % period;
+        // A triangle wave: up during the first half of the period and back down in the second.
+        let level = if step < half { step } else { period - step };
+        buf.fill((level * self.brightness as u64 / half) as u8);
+    }
+}
+
+/// A single lit LED moving through the LEDs in order, leaving a trail that dims by half every
+/// frame.
+#[cfg(feature = "animations")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chase {
+    pub brightness: u8,
+}
+
+#[cfg(feature = "animations")]
+impl Animation for Chase {
+    fn render(&mut self, frame: u64, buf: &mut [u8; LED_COUNT]) {
+        for value in buf.iter_mut() {
+            *value /= 2;
+        }
+        buf[(frame % LED_COUNT as u64) as usize] = self.brightness;
+    }
+}
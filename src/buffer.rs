@@ -0,0 +1,199 @@
+use crate::{Error, IS31FL3731, LED_COUNT};
+use embedded_hal::i2c::I2c;
+
+/// How a new brightness is combined with the brightness already in a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Overwrite the existing brightness.
+    Replace,
+    /// Add to the existing brightness, saturating at 255.
+    Add,
+    /// Keep the brighter of the two.
+    Max,
+    /// Multiply the two, treating 255 as 1.0.
+    Multiply,
+}
+
+impl BlendMode {
+    /// Combine `value` onto `existing`.
+    pub fn apply(self, existing: u8, value: u8) -> u8 {
+        match self {
+            BlendMode::Replace => value,
+            BlendMode::Add => existing.saturating_add(value),
+            BlendMode::Max => existing.max(value),
+            BlendMode::Multiply => (existing as u16 * value as u16 / 255) as u8,
+        }
+    }
+}
+
+/// A buffer of brightness values sized to the coordinate space of a device's `calc_pixel`
+/// function. Drawing happens in RAM and is sent to the device in one write by the device's
+/// `flush_blocking` method. For RGB devices `W` is the number of pixels and `H` is the 3 color
+/// channels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceBuffer<const W: usize, const H: usize> {
+    /// The brightness values indexed by `[x][y]`.
+    pub data: [[u8; H]; W],
+}
+
+impl<const W: usize, const H: usize> DeviceBuffer<W, H> {
+    /// The width of the buffer.
+    pub const WIDTH: usize = W;
+    /// The height of the buffer.
+    pub const HEIGHT: usize = H;
+
+    /// Create a new buffer with every LED turned off.
+    pub const fn new() -> Self {
+        Self { data: [[0; H]; W] }
+    }
+
+    /// Get the brightness at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= W` or `y >= H`.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.data[x][y]
+    }
+
+    /// Set the brightness at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= W` or `y >= H`.
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        self.data[x][y] = value;
+    }
+
+    /// Combine `value` with the brightness already at `(x, y)` using `mode`. Like the rest of the
+    /// buffer this only changes RAM, the result shows up on the next flush.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= W` or `y >= H`.
+    pub fn blend_pixel(&mut self, x: usize, y: usize, value: u8, mode: BlendMode) {
+        self.data[x][y] = mode.apply(self.data[x][y], value);
+    }
+
+    /// Set every LED to a single brightness.
+    pub fn fill(&mut self, value: u8) {
+        self.data = [[value; H]; W];
+    }
+
+    /// Turn every LED off.
+    pub fn clear(&mut self) {
+        self.fill(0);
+    }
+
+    /// Lay the buffer out as the 144 LEDs of the chip using a device's `calc_pixel` function.
+    #[cfg(any(
+        feature = "eleven_7",
+        feature = "led_shim",
+        feature = "matrix",
+        feature = "rgb_matrix_5x5"
+    ))]
+    pub(crate) fn render<E>(
+        &self,
+        calc_pixel: fn(u8, u8) -> Result<u8, Error<E>>,
+    ) -> Result<[u8; LED_COUNT], Error<E>> {
+        let mut frame = [0; LED_COUNT];
+        for (x, column) in self.data.iter().enumerate() {
+            for (y, value) in column.iter().enumerate() {
+                frame[calc_pixel(x as u8, y as u8)? as usize] = *value;
+            }
+        }
+        Ok(frame)
+    }
+}
+
+impl<const W: usize, const H: usize> Default for DeviceBuffer<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A buffer of colors for an RGB board, indexed by `(x, y)` in the board's RGB pixel coordinates
+/// rather than by color channel. The mapping function gives the LEDs of the red, green and blue
+/// channels of each pixel, which is the only thing that differs between boards, so drawing and
+/// animation code written against a canvas works on any RGB board. The RGB devices create a
+/// canvas with their mapping through their `canvas` function and send it with
+/// `flush_canvas_blocking`, which also applies their channel order and gamma.
+#[derive(Clone, Copy)]
+pub struct RgbCanvas<const W: usize, const H: usize> {
+    /// The colors indexed by `[x][y]`.
+    pub data: [[(u8, u8, u8); H]; W],
+    map: fn(u8, u8) -> [u8; 3],
+}
+
+impl<const W: usize, const H: usize> RgbCanvas<W, H> {
+    /// The width of the canvas in pixels.
+    pub const WIDTH: usize = W;
+    /// The height of the canvas in pixels.
+    pub const HEIGHT: usize = H;
+
+    /// Create a new canvas with every pixel turned off. `map` gives the red, green and blue LEDs
+    /// of the pixel at `(x, y)` and is only called with `x < W` and `y < H`.
+    pub const fn new(map: fn(u8, u8) -> [u8; 3]) -> Self {
+        Self {
+            data: [[(0, 0, 0); H]; W],
+            map,
+        }
+    }
+
+    /// Get the color at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= W` or `y >= H`.
+    pub fn get(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        self.data[x][y]
+    }
+
+    /// Set the color at `(x, y)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x >= W` or `y >= H`.
+    pub fn set(&mut self, x: usize, y: usize, color: (u8, u8, u8)) {
+        self.data[x][y] = color;
+    }
+
+    /// Set every pixel to a single color.
+    pub fn fill(&mut self, color: (u8, u8, u8)) {
+        self.data = [[color; H]; W];
+    }
+
+    /// Turn every pixel off.
+    pub fn clear(&mut self) {
+        self.fill((0, 0, 0));
+    }
+
+    /// Lay the canvas out as the 144 LEDs of the chip, passing every color through `channels`
+    /// first. LEDs that no pixel maps to are off.
+    pub fn render(&self, channels: impl Fn(u8, u8, u8) -> (u8, u8, u8)) -> [u8; LED_COUNT] {
+        let mut frame = [0; LED_COUNT];
+        for (x, column) in self.data.iter().enumerate() {
+            for (y, &(r, g, b)) in column.iter().enumerate() {
+                let [r_led, g_led, b_led] = (self.map)(x as u8, y as u8);
+                let (r, g, b) = channels(r, g, b);
+                frame[r_led as usize] = r;
+                frame[g_led as usize] = g;
+                frame[b_led as usize] = b;
+            }
+        }
+        frame
+    }
+
+    /// Send the whole canvas to the current frame of `device` in a single write, without any
+    /// channel order or gamma. Prefer the `flush_canvas_blocking` method of a device wrapper when
+    /// there is one.
+    pub fn flush_blocking<I2C, I2cError>(
+        &self,
+        device: &mut IS31FL3731<I2C>,
+    ) -> Result<(), Error<I2cError>>
+    where
+        I2C: I2c<Error = I2cError>,
+    {
+        device.all_pixels_blocking(&self.render(|r, g, b| (r, g, b)))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Error, IS31FL3731};
+use embedded_hal::i2c::I2c;
+
+/// Several IS31FL3731 devices at different addresses combined into a single logical display, for
+/// example two [Matrix](crate::devices::Matrix) boards side by side forming a 32x9 display.
+///
+/// Each device takes its own I2C handle. When the devices share a bus use one of the shared bus
+/// wrappers from [embedded-hal-bus](https://docs.rs/embedded-hal-bus) (e.g. `RefCellDevice`) to
+/// create a handle per device.
+pub struct Chain<I2C, const N: usize> {
+    /// The devices making up the chain. The index of a device in this array is the index returned
+    /// by the mapping function.
+    pub devices: [IS31FL3731<I2C>; N],
+    /// Maps an `(x, y)` coordinate on the combined display to a `(device, led)` pair, or `None`
+    /// if the coordinate is outside the display.
+    map: fn(u8, u8) -> Option<(usize, u8)>,
+}
+
+impl<I2C, const N: usize> Chain<I2C, N> {
+    /// Create a new chain from already configured devices and a coordinate mapping function.
+    pub fn new(devices: [IS31FL3731<I2C>; N], map: fn(u8, u8) -> Option<(usize, u8)>) -> Self {
+        Self { devices, map }
+    }
+}
+
+impl<I2C, I2cError, const N: usize> Chain<I2C, N>
+where
+    I2C: I2c<Error = I2cError>,
+{
+    /// Set the brightness of the LED at `(x, y)` on the combined display. If the mapping function
+    /// doesn't know the coordinate then an error of [InvalidLocation](Error::InvalidLocation) is
+    /// returned.
+    pub fn pixel_blocking(&mut self, x: u8, y: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        let (device, led) = (self.map)(x, y).ok_or(Error::InvalidLocation(x))?;
+        self.devices
+            .get_mut(device)
+            .ok_or(Error::InvalidLocation(x))?
+            .pixel_blocking(led, brightness)
+    }
+
+    /// Clear the current frame of every device in the chain.
+    pub fn clear_blocking(&mut self) -> Result<(), I2cError> {
+        for device in self.devices.iter_mut() {
+            device.clear_blocking()?;
+        }
+        Ok(())
+    }
+}
@@ -1,37 +1,594 @@
-#[allow(unused_imports)]
-use crate::{Error, IS31FL3731};
-#[allow(unused_imports)]
+//! Every board uses the same coordinate convention: `(0, 0)` is the top left LED with the board
+//! in its usual orientation, `x` grows to the right and `y` grows down, up to the board's
+//! `dimensions()`. The `calc_pixel` functions take care of each board's wiring quirks, so code
+//! written against one board draws the same way on another. Boards that are mounted rotated or
+//! mirrored can be brought back to this convention with an
+//! [Orientation](crate::devices::Orientation).
+
+#[cfg(any(
+    feature = "eleven_7",
+    feature = "led_shim",
+    feature = "matrix",
+    feature = "rgb_matrix_5x5"
+))]
+use crate::buffer::DeviceBuffer;
+#[cfg(any(
+    feature = "keybow_2040",
+    feature = "led_shim",
+    feature = "rgb_matrix_5x5"
+))]
+use crate::buffer::RgbCanvas;
+#[cfg(any(feature = "led_shim", feature = "rgb_matrix_5x5"))]
+use crate::hsv_to_rgb;
+#[cfg(any(
+    feature = "charlie_bonnet",
+    feature = "charlie_wing",
+    feature = "eleven_7",
+    feature = "matrix",
+    feature = "scroll_phat_hd"
+))]
+use crate::sprite::Sprite;
+use crate::{Error, IS31FL3731, LED_COUNT};
+#[cfg(all(
+    feature = "graphics",
+    any(
+        feature = "keybow_2040",
+        feature = "led_shim",
+        feature = "rgb_matrix_5x5"
+    )
+))]
+use embedded_graphics_core::{
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Size},
+    pixelcolor::{Rgb888, RgbColor},
+    Pixel,
+};
+#[cfg(any(
+    feature = "keybow_2040",
+    feature = "led_shim",
+    feature = "rgb_matrix_5x5"
+))]
 use embedded_hal::delay::DelayNs;
-#[allow(unused_imports)]
 use embedded_hal::i2c::I2c;
 
+/// The order in which an RGB board wires its color channels relative to the lookup used by the
+/// driver. Change it with `set_channel_order` if your board shows swapped colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ChannelOrder {
+    #[default]
+    Rgb,
+    Grb,
+    Bgr,
+}
+
+impl ChannelOrder {
+    /// Reorder a color into the values for the board's first, second and third channel.
+    pub fn apply(self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+        match self {
+            ChannelOrder::Rgb => (r, g, b),
+            ChannelOrder::Grb => (g, r, b),
+            ChannelOrder::Bgr => (b, g, r),
+        }
+    }
+}
+
+/// How a board is mounted relative to its usual orientation. [apply](Self::apply) maps
+/// coordinates in the mounted orientation to the board's own coordinates, so drawing code keeps
+/// the top left origin whichever way the board is mounted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Orientation {
+    #[default]
+    Normal,
+    /// Upside down.
+    Rotate180,
+    /// Mirrored left to right.
+    FlipX,
+    /// Mirrored top to bottom.
+    FlipY,
+}
+
+impl Orientation {
+    /// Map `(x, y)` on a board of `width` by `height`, as returned by the board's `dimensions()`,
+    /// to the board's own coordinates. Coordinates outside the board are returned unchanged so
+    /// that the board still reports them as invalid.
+    pub fn apply(self, x: u8, y: u8, (width, height): (u8, u8)) -> (u8, u8) {
+        if x >= width || y >= height {
+            return (x, y);
+        }
+        match self {
+            Orientation::Normal => (x, y),
+            Orientation::Rotate180 => (width - 1 - x, height - 1 - y),
+            Orientation::FlipX => (width - 1 - x, y),
+            Orientation::FlipY => (x, height - 1 - y),
+        }
+    }
+}
+
+/// Per channel gamma correction applied by the RGB device wrappers when set with
+/// `set_channel_gamma`. Defaults to [GAMMA_R](crate::GAMMA_R), [GAMMA_G](crate::GAMMA_G) and
+/// [GAMMA_B](crate::GAMMA_B) but any table can be used.
+#[cfg(feature = "channel_gamma")]
+#[derive(Clone, Copy, Debug)]
+pub struct ChannelGamma {
+    pub r: &'static [u8; 256],
+    pub g: &'static [u8; 256],
+    pub b: &'static [u8; 256],
+}
+
+#[cfg(feature = "channel_gamma")]
+impl Default for ChannelGamma {
+    fn default() -> Self {
+        Self {
+            r: &crate::GAMMA_R,
+            g: &crate::GAMMA_G,
+            b: &crate::GAMMA_B,
+        }
+    }
+}
+
+#[cfg(feature = "channel_gamma")]
+impl ChannelGamma {
+    /// Gamma correct each channel of a color.
+    pub fn apply(&self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+        (self.r[r as usize], self.g[g as usize], self.b[b as usize])
+    }
+}
+
+/// Build an enable mask with only the LEDs that `calc_pixel` maps to for every coordinate within
+/// `width` by `height` turned on.
+#[cfg(any(
+    feature = "charlie_bonnet",
+    feature = "charlie_wing",
+    feature = "eleven_7",
+    feature = "keybow_2040",
+    feature = "led_shim",
+    feature = "matrix",
+    feature = "rgb_matrix_5x5",
+    feature = "scroll_phat_hd"
+))]
+fn mapped_mask<E>(
+    width: u8,
+    height: u8,
+    calc_pixel: fn(u8, u8) -> Result<u8, Error<E>>,
+) -> [u8; 18] {
+    let mut mask = [0; 18];
+    for x in 0..width {
+        for y in 0..height {
+            if let Ok(led) = calc_pixel(x, y) {
+                mask[led as usize / 8] |= 1 << (led % 8);
+            }
+        }
+    }
+    mask
+}
+
+/// The three channel writes of an RGB pixel sorted by LED, so that
+/// [apply_delta](IS31FL3731::apply_delta_blocking) merges channels on neighbouring LEDs into a
+/// single write.
+fn rgb_delta(leds: [u8; 3], (r, g, b): (u8, u8, u8)) -> [(u8, u8); 3] {
+    let mut delta = [(leds[0], r), (leds[1], g), (leds[2], b)];
+    delta.sort_unstable_by_key(|&(led, _)| led);
+    delta
+}
+
+/// `(x, y)` on a canvas moved into the coordinates of a board of `width` by `height` showing the
+/// canvas from `(dx, dy)`, or `None` if that lands outside the board. Without an offset the
+/// coordinates are returned as they are so that out of range coordinates are still reported by
+/// `calc_pixel`.
+#[cfg(any(
+    feature = "charlie_bonnet",
+    feature = "charlie_wing",
+    feature = "eleven_7",
+    feature = "matrix",
+    feature = "scroll_phat_hd"
+))]
+fn apply_viewport(
+    x: u8,
+    y: u8,
+    (dx, dy): (i16, i16),
+    (width, height): (u8, u8),
+) -> Option<(u8, u8)> {
+    if (dx, dy) == (0, 0) {
+        return Some((x, y));
+    }
+    let x = u8::try_from(x as i32 - dx as i32).ok()?;
+    let y = u8::try_from(y as i32 - dy as i32).ok()?;
+    (x < width && y < height).then_some((x, y))
+}
+
+/// Whether every LED in an RGB lookup table is on the chip and no LED is used twice, checked at
+/// compile time to catch mistakes in hand written tables.
+#[cfg(any(feature = "keybow_2040", feature = "rgb_matrix_5x5"))]
+const fn valid_lookup<const N: usize>(lookup: &[[u8; 3]; N]) -> bool {
+    let mut used = [false; LED_COUNT];
+    let mut pixel = 0;
+    while pixel < N {
+        let mut channel = 0;
+        while channel < 3 {
+            let led = lookup[pixel][channel] as usize;
+            if led >= LED_COUNT || used[led] {
+                return false;
+            }
+            used[led] = true;
+            channel += 1;
+        }
+        pixel += 1;
+    }
+    true
+}
+
+/// The names of the device features this crate was built with, in alphabetical order. Useful for
+/// firmware that supports several boards to log what is available at startup.
+pub const SUPPORTED_DEVICES: &[&str] = &[
+    #[cfg(feature = "charlie_bonnet")]
+    "charlie_bonnet",
+    #[cfg(feature = "charlie_wing")]
+    "charlie_wing",
+    #[cfg(feature = "eleven_7")]
+    "eleven_7",
+    #[cfg(feature = "keybow_2040")]
+    "keybow_2040",
+    #[cfg(feature = "led_shim")]
+    "led_shim",
+    #[cfg(feature = "matrix")]
+    "matrix",
+    #[cfg(feature = "rgb_matrix_5x5")]
+    "rgb_matrix_5x5",
+    #[cfg(feature = "scroll_phat_hd")]
+    "scroll_phat_hd",
+];
+
+/// Get the names of the device features this crate was built with, see
+/// [SUPPORTED_DEVICES].
+pub const fn supported_devices() -> &'static [&'static str] {
+    SUPPORTED_DEVICES
+}
+
+/// An in-memory copy of the LEDs drawn to through embedded-graphics, along with the range of LEDs
+/// changed since it was last sent.
+#[cfg(all(
+    feature = "graphics",
+    any(
+        feature = "keybow_2040",
+        feature = "led_shim",
+        feature = "rgb_matrix_5x5"
+    )
+))]
+struct DrawBuffer {
+    leds: [u8; LED_COUNT],
+    dirty: Option<(u8, u8)>,
+}
+
+#[cfg(all(
+    feature = "graphics",
+    any(
+        feature = "keybow_2040",
+        feature = "led_shim",
+        feature = "rgb_matrix_5x5"
+    )
+))]
+impl DrawBuffer {
+    const fn new() -> Self {
+        Self {
+            leds: [0; LED_COUNT],
+            dirty: None,
+        }
+    }
+
+    /// Store the three channels of RGB pixel `index`, skipping any channel the device doesn't
+    /// map.
+    fn draw_rgb(
+        &mut self,
+        index: u8,
+        (r, g, b): (u8, u8, u8),
+        calc_pixel: fn(u8, u8) -> Result<u8, Error<core::convert::Infallible>>,
+    ) {
+        for (channel, value) in [r, g, b].into_iter().enumerate() {
+            if let Ok(led) = calc_pixel(index, channel as u8) {
+                if self.leds[led as usize] != value {
+                    self.leds[led as usize] = value;
+                    self.dirty = match self.dirty {
+                        Some((first, last)) => Some((first.min(led), last.max(led))),
+                        None => Some((led, led)),
+                    };
+                }
+            }
+        }
+    }
+
+    /// The first changed LED and the values from it up to the last changed LED.
+    fn dirty_span(&self) -> Option<(u8, &[u8])> {
+        self.dirty
+            .map(|(first, last)| (first, &self.leds[first as usize..=last as usize]))
+    }
+}
+
+/// The methods shared by every single color board, using the board's `ADDRESS`, its `viewport`
+/// field and its `calc_pixel` for coordinates within its `WIDTH` by `HEIGHT`. The doc comment
+/// passed in documents the board's `enable_only_mapped_blocking`.
+#[cfg(any(
+    feature = "charlie_bonnet",
+    feature = "charlie_wing",
+    feature = "eleven_7",
+    feature = "matrix",
+    feature = "scroll_phat_hd"
+))]
+macro_rules! mono_board {
+    ($board:ident, $(#[$enable_doc:meta])*) => {
+        impl<I2C> $board<I2C> {
+            /// Whether `(x, y)` is on the board, i.e. whether [calc_pixel](Self::calc_pixel) will
+            /// accept it.
+            pub fn is_valid(x: u8, y: u8) -> bool {
+                x < Self::WIDTH && y < Self::HEIGHT
+            }
+
+            /// The `(width, height)` of the board.
+            pub const fn dimensions() -> (u8, u8) {
+                (Self::WIDTH, Self::HEIGHT)
+            }
+
+            /// Change the i2c address for a board whose address jumpers have been changed from
+            /// [ADDRESS](Self::ADDRESS). Should be called before setting up the board.
+            pub fn set_address(&mut self, address: u8) {
+                self.device.set_address(address);
+            }
+
+            /// Treat the board as a window onto a larger canvas whose top left corner is at
+            /// `(dx, dy)` on the canvas. Coordinates passed to
+            /// [pixel_xy_blocking](Self::pixel_xy_blocking),
+            /// [pixel_xy_clipping_blocking](Self::pixel_xy_clipping_blocking) and
+            /// [draw_sprite_blocking](Self::draw_sprite_blocking) are then canvas coordinates,
+            /// and anything outside the window is clipped. Changing the offset pans the window
+            /// across the canvas. An offset of `(0, 0)` restores the normal behavior.
+            pub fn set_viewport_offset(&mut self, dx: i16, dy: i16) {
+                self.viewport = (dx, dy);
+            }
+        }
+
+        impl<I2C, I2cError> $board<I2C>
+        where
+            I2C: I2c<Error = I2cError>,
+        {
+            pub fn configure(i2c: I2C) -> Self {
+                Self {
+                    device: IS31FL3731::new(i2c, Self::ADDRESS),
+                    viewport: (0, 0),
+                }
+            }
+
+            /// Set the brightness of the LED at `(x, y)`. For gamma corrected brightness pass the
+            /// value through [gamma](crate::gamma) first.
+            pub fn pixel_xy_blocking(
+                &mut self,
+                x: u8,
+                y: u8,
+                brightness: u8,
+            ) -> Result<(), Error<I2cError>> {
+                let Some((x, y)) = apply_viewport(x, y, self.viewport, Self::dimensions()) else {
+                    return Ok(());
+                };
+                self.device
+                    .pixel_blocking(Self::calc_pixel(x, y)?, brightness)
+            }
+
+            /// Like [pixel_xy_blocking](Self::pixel_xy_blocking) but coordinates past the edge of
+            /// the board wrap around to the other side, which is handy for scrolling effects. The
+            /// viewport offset isn't applied.
+            pub fn pixel_xy_wrapping_blocking(
+                &mut self,
+                x: u8,
+                y: u8,
+                brightness: u8,
+            ) -> Result<(), Error<I2cError>> {
+                self.device.pixel_blocking(
+                    Self::calc_pixel(x % Self::WIDTH, y % Self::HEIGHT)?,
+                    brightness,
+                )
+            }
+
+            /// Like [pixel_xy_blocking](Self::pixel_xy_blocking) but coordinates past the edge of
+            /// the board are silently ignored instead of returning an error.
+            pub fn pixel_xy_clipping_blocking(
+                &mut self,
+                x: u8,
+                y: u8,
+                brightness: u8,
+            ) -> Result<(), Error<I2cError>> {
+                match apply_viewport(x, y, self.viewport, Self::dimensions()) {
+                    Some((x, y)) if Self::is_valid(x, y) => self
+                        .device
+                        .pixel_blocking(Self::calc_pixel(x, y)?, brightness),
+                    _ => Ok(()),
+                }
+            }
+
+            /// Draw `sprite` with its top left corner at `(x, y)`. Coordinates may be negative or
+            /// past the edge of the board, the parts of the sprite that don't land on the board
+            /// are clipped.
+            pub fn draw_sprite_blocking(
+                &mut self,
+                sprite: &Sprite,
+                x: i16,
+                y: i16,
+            ) -> Result<(), Error<I2cError>> {
+                let (dx, dy) = self.viewport;
+                let (x, y) = (x.saturating_sub(dx), y.saturating_sub(dy));
+                for (x, y, brightness) in sprite.clipped(x, y, Self::dimensions()) {
+                    self.device
+                        .pixel_blocking(Self::calc_pixel(x, y)?, brightness)?;
+                }
+                Ok(())
+            }
+
+            $(#[$enable_doc])*
+            pub fn enable_only_mapped_blocking(&mut self) -> Result<(), I2cError> {
+                self.device.set_enable_mask_blocking(&mapped_mask(
+                    Self::WIDTH,
+                    Self::HEIGHT,
+                    Self::calc_pixel,
+                ))
+            }
+        }
+    };
+}
+
+/// The methods shared by every RGB board, using the board's `ADDRESS`, its `calc_pixel` for the
+/// `LED_COUNT / 3` pixels of 3 color channels each and its `channel_order` and `channel_gamma`
+/// fields. The doc comment passed in documents the board's `enable_only_mapped_blocking`.
+#[cfg(any(
+    feature = "keybow_2040",
+    feature = "led_shim",
+    feature = "rgb_matrix_5x5"
+))]
+macro_rules! rgb_board {
+    ($board:ident, $(#[$enable_doc:meta])*) => {
+        impl<I2C> $board<I2C> {
+            pub fn configure(i2c: I2C) -> Self {
+                Self {
+                    device: IS31FL3731::new(i2c, Self::ADDRESS),
+                    channel_order: ChannelOrder::Rgb,
+                    #[cfg(feature = "channel_gamma")]
+                    channel_gamma: None,
+                    #[cfg(feature = "graphics")]
+                    draw_buffer: DrawBuffer::new(),
+                }
+            }
+
+            /// Set the order the board wires its color channels in. Use this if colors come out
+            /// swapped, e.g. red showing up as blue.
+            pub fn set_channel_order(&mut self, order: ChannelOrder) {
+                self.channel_order = order;
+            }
+
+            /// The `(width, height)` of the board in RGB pixels.
+            pub const fn dimensions() -> (u8, u8) {
+                (Self::WIDTH, Self::HEIGHT)
+            }
+
+            /// Change the i2c address for a board whose address jumpers have been changed from
+            /// [ADDRESS](Self::ADDRESS). Should be called before setting up the board.
+            pub fn set_address(&mut self, address: u8) {
+                self.device.set_address(address);
+            }
+
+            /// Apply the configured gamma and channel order to a color, giving the values for the
+            /// board's first, second and third channel.
+            fn channels(&self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+                #[cfg(feature = "channel_gamma")]
+                let (r, g, b) = match &self.channel_gamma {
+                    Some(gamma) => gamma.apply(r, g, b),
+                    None => (r, g, b),
+                };
+                self.channel_order.apply(r, g, b)
+            }
+
+            /// Gamma correct each color channel with its own table before writing it, or stop
+            /// gamma correcting with `None`.
+            #[cfg(feature = "channel_gamma")]
+            pub fn set_channel_gamma(&mut self, gamma: Option<ChannelGamma>) {
+                self.channel_gamma = gamma;
+            }
+
+            /// Whether `x` is a pixel on the board and `y` is one of its 3 color channels, the
+            /// coordinate space used by [calc_pixel](Self::calc_pixel).
+            pub fn is_valid(x: u8, y: u8) -> bool {
+                (x as usize) < Self::LED_COUNT / 3 && y < 3
+            }
+        }
+
+        impl<I2C, I2cError> $board<I2C>
+        where
+            I2C: I2c<Error = I2cError>,
+        {
+            $(#[$enable_doc])*
+            pub fn enable_only_mapped_blocking(&mut self) -> Result<(), I2cError> {
+                self.device.set_enable_mask_blocking(&mapped_mask(
+                    (Self::LED_COUNT / 3) as u8,
+                    3,
+                    Self::calc_pixel::<I2cError>,
+                ))
+            }
+        }
+    };
+}
+
 #[cfg(feature = "charlie_bonnet")]
 pub struct CharlieBonnet<I2C> {
     pub device: IS31FL3731<I2C>,
+    viewport: (i16, i16),
 }
 #[cfg(feature = "charlie_wing")]
 pub struct CharlieWing<I2C> {
     pub device: IS31FL3731<I2C>,
+    viewport: (i16, i16),
 }
+#[cfg(feature = "eleven_7")]
+pub struct Eleven7<I2C> {
+    pub device: IS31FL3731<I2C>,
+    viewport: (i16, i16),
+}
+/// A buffer covering every LED of the [Eleven7].
+#[cfg(feature = "eleven_7")]
+pub type Eleven7Buffer = DeviceBuffer<11, 7>;
 #[cfg(feature = "keybow_2040")]
 pub struct Keybow2040<I2C> {
     pub device: IS31FL3731<I2C>,
+    channel_order: ChannelOrder,
+    #[cfg(feature = "channel_gamma")]
+    channel_gamma: Option<ChannelGamma>,
+    #[cfg(feature = "graphics")]
+    draw_buffer: DrawBuffer,
 }
 #[cfg(feature = "led_shim")]
 pub struct LEDShim<I2C> {
     pub device: IS31FL3731<I2C>,
+    channel_order: ChannelOrder,
+    #[cfg(feature = "channel_gamma")]
+    channel_gamma: Option<ChannelGamma>,
+    #[cfg(feature = "graphics")]
+    draw_buffer: DrawBuffer,
 }
+/// A buffer covering the 28 pixels of the [LEDShim], indexed by `(x, channel)` where channel 0, 1
+/// and 2 are red, green and blue.
+#[cfg(feature = "led_shim")]
+pub type LEDShimBuffer = DeviceBuffer<28, 3>;
 #[cfg(feature = "matrix")]
 pub struct Matrix<I2C> {
     pub device: IS31FL3731<I2C>,
+    viewport: (i16, i16),
 }
+/// A buffer covering every LED of the [Matrix].
+#[cfg(feature = "matrix")]
+pub type MatrixBuffer = DeviceBuffer<16, 9>;
 #[cfg(feature = "rgb_matrix_5x5")]
 pub struct RGBMatrix5x5<I2C> {
     pub device: IS31FL3731<I2C>,
+    channel_order: ChannelOrder,
+    #[cfg(feature = "channel_gamma")]
+    channel_gamma: Option<ChannelGamma>,
+    #[cfg(feature = "graphics")]
+    draw_buffer: DrawBuffer,
 }
+/// A buffer covering the 25 pixels of the [RGBMatrix5x5], indexed by `(x + y * 5, channel)` where
+/// channel 0, 1 and 2 are red, green and blue.
+#[cfg(feature = "rgb_matrix_5x5")]
+pub type RGBMatrix5x5Buffer = DeviceBuffer<25, 3>;
 #[cfg(feature = "scroll_phat_hd")]
 pub struct ScrollPhatHD<I2C> {
     pub device: IS31FL3731<I2C>,
+    viewport: (i16, i16),
+}
+
+#[cfg(feature = "charlie_bonnet")]
+impl<I2C> CharlieBonnet<I2C> {
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x74;
+    /// The number of columns on the board.
+    pub const WIDTH: u8 = 16;
+    /// The number of rows on the board.
+    pub const HEIGHT: u8 = 8;
+    /// The number of LEDs on the board.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
 }
 
 #[cfg(feature = "charlie_bonnet")]
@@ -39,19 +596,11 @@ impl<I2C, I2cError> CharlieBonnet<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
-    pub fn configure(i2c: I2C) -> IS31FL3731<I2C> {
-        IS31FL3731 {
-            i2c,
-            address: 0x74,
-            frame: 0,
-        }
-    }
-
     pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
-        if x > 16 {
+        if x >= Self::WIDTH {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 8 {
+        if y >= Self::HEIGHT {
             return Err(Error::InvalidLocation(y));
         }
         Ok(if x >= 8 {
@@ -62,76 +611,191 @@ where
     }
 }
 
+#[cfg(feature = "charlie_bonnet")]
+mono_board! {
+    CharlieBonnet,
+    /// Enable only the 128 LEDs wired to the bonnet, disabling the 16 outputs of the chip that
+    /// have no LED.
+}
+
+/// The LED at `(x, y)` on the [CharlieWing], which must be on the board.
+#[cfg(feature = "charlie_wing")]
+const fn charlie_wing_led(x: u8, y: u8) -> u8 {
+    if x > 7 {
+        (15 - x) * 16 + y + 8
+    } else {
+        x * 16 + 7 - y
+    }
+}
+
+/// Every LED used by the [CharlieWing] in ascending order, built from a sweep of its coordinates.
+#[cfg(feature = "charlie_wing")]
+const fn charlie_wing_mapped_leds() -> [u8; 105] {
+    let mut used = [false; LED_COUNT];
+    let mut x = 0;
+    while x < 15 {
+        let mut y = 0;
+        while y < 7 {
+            used[charlie_wing_led(x, y) as usize] = true;
+            y += 1;
+        }
+        x += 1;
+    }
+    let mut leds = [0; 105];
+    let mut count = 0;
+    let mut led = 0;
+    while led < LED_COUNT {
+        if used[led] {
+            leds[count] = led as u8;
+            count += 1;
+        }
+        led += 1;
+    }
+    // Fails to compile if two coordinates share an LED.
+    assert!(count == 105);
+    leds
+}
+
+#[cfg(feature = "charlie_wing")]
+impl<I2C> CharlieWing<I2C> {
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x74;
+    /// The number of columns on the board.
+    pub const WIDTH: u8 = 15;
+    /// The number of rows on the board.
+    pub const HEIGHT: u8 = 7;
+    /// The number of LEDs on the board.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
+    /// The LEDs wired to the board, in ascending order. The other 39 LEDs of the chip aren't
+    /// connected to anything.
+    pub const MAPPED_LEDS: [u8; 105] = charlie_wing_mapped_leds();
+}
+
 #[cfg(feature = "charlie_wing")]
 impl<I2C, I2cError> CharlieWing<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
-    pub fn configure(i2c: I2C) -> IS31FL3731<I2C> {
-        IS31FL3731 {
-            i2c,
-            address: 0x74,
-            frame: 0,
+    pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
+        Ok(charlie_wing_led(x, y))
+    }
+
+    /// Turn off the 105 LEDs wired to the board, leaving the unconnected ones untouched.
+    pub fn clear_mapped_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        let mut changes = [(0, 0); 105];
+        for (change, led) in changes.iter_mut().zip(Self::MAPPED_LEDS) {
+            change.0 = led;
         }
+        self.device.apply_delta_blocking(&changes)
     }
+}
+
+#[cfg(feature = "charlie_wing")]
+mono_board! {
+    CharlieWing,
+    /// Enable only the 105 LEDs wired to the wing, disabling the 39 outputs of the chip that have
+    /// no LED.
+}
 
+#[cfg(feature = "eleven_7")]
+impl<I2C> Eleven7<I2C> {
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x75;
+    /// The number of columns on the board.
+    pub const WIDTH: u8 = 11;
+    /// The number of rows on the board.
+    pub const HEIGHT: u8 = 7;
+    /// The number of LEDs on the board.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
+}
+
+#[cfg(feature = "eleven_7")]
+impl<I2C, I2cError> Eleven7<I2C>
+where
+    I2C: I2c<Error = I2cError>,
+{
     pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
-        if x > 15 {
+        if x >= Self::WIDTH {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 7 {
+        if y >= Self::HEIGHT {
             return Err(Error::InvalidLocation(y));
         }
-        let mut x = x;
-        let mut y = y;
-        if x > 7 {
-            x -= 15;
-            y += 8;
+        Ok(if x <= 5 {
+            x * 16 + 6 - y
         } else {
-            y = 7 - y
-        }
-        Ok(x * 16 + y)
+            x * 16 - 82 - y
+        })
+    }
+
+    /// Send the whole buffer to the current frame in a single write.
+    pub fn flush_blocking(&mut self, buffer: &Eleven7Buffer) -> Result<(), Error<I2cError>> {
+        self.device
+            .all_pixels_blocking(&buffer.render(Self::calc_pixel)?)
     }
 }
 
+#[cfg(feature = "eleven_7")]
+mono_board! {
+    Eleven7,
+    /// Enable only the 77 LEDs wired to the board, disabling the 67 outputs of the chip that have
+    /// no LED.
+}
+
+/// The LED of each color channel of every [Keybow2040] pixel.
+#[cfg(feature = "keybow_2040")]
+const KEYBOW_2040_LOOKUP: [[u8; 3]; 16] = [
+    [120, 88, 104],
+    [136, 40, 72],
+    [112, 80, 96],
+    [128, 32, 64],
+    [121, 89, 105],
+    [137, 41, 73],
+    [113, 81, 97],
+    [129, 33, 65],
+    [122, 90, 106],
+    [138, 25, 74],
+    [114, 82, 98],
+    [130, 17, 66],
+    [123, 91, 107],
+    [139, 26, 75],
+    [115, 83, 99],
+    [131, 18, 67],
+];
+#[cfg(feature = "keybow_2040")]
+const _: () = assert!(valid_lookup(&KEYBOW_2040_LOOKUP));
+
 #[cfg(feature = "keybow_2040")]
 impl<I2C> Keybow2040<I2C> {
-    pub fn configure(i2c: I2C) -> Self {
-        Self {
-            device: IS31FL3731 {
-                i2c,
-                address: 0x74,
-                frame: 0,
-            },
-        }
-    }
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x74;
+    /// The number of RGB pixel columns on the board.
+    pub const WIDTH: u8 = 4;
+    /// The number of RGB pixel rows on the board.
+    pub const HEIGHT: u8 = 4;
+    /// The number of LEDs on the board, three for every RGB pixel.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize * 3;
 
     pub fn calc_pixel<E>(x: u8, y: u8) -> Result<u8, Error<E>> {
-        if x > 16 {
+        if x > 15 {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 3 {
+        if y > 2 {
             return Err(Error::InvalidLocation(y));
         }
-        let lookup = [
-            [120, 88, 104],
-            [136, 40, 72],
-            [112, 80, 96],
-            [128, 32, 64],
-            [121, 89, 105],
-            [137, 41, 73],
-            [113, 81, 97],
-            [129, 33, 65],
-            [122, 90, 106],
-            [138, 25, 74],
-            [114, 82, 98],
-            [130, 17, 66],
-            [123, 91, 107],
-            [139, 26, 75],
-            [115, 83, 99],
-            [131, 18, 67],
-        ];
-        Ok(lookup[x as usize][y as usize])
+        Ok(KEYBOW_2040_LOOKUP[x as usize][y as usize])
+    }
+
+    /// A canvas of the board's 4 by 4 keys, to be sent with
+    /// [flush_canvas](Self::flush_canvas_blocking).
+    pub const fn canvas() -> RgbCanvas<4, 4> {
+        RgbCanvas::new(|x, y| KEYBOW_2040_LOOKUP[(4 * (3 - x) + y) as usize])
     }
 }
 
@@ -140,6 +804,48 @@ impl<I2C, I2cError> Keybow2040<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
+    /// Setup the display. Only frame 0 is used by this board so only that frame is cleared,
+    /// which is much faster than the full [setup](IS31FL3731::setup_blocking). Only the 48 LEDs
+    /// wired to the keys are enabled, which avoids wasting current on unconnected nodes.
+    pub fn setup_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2cError>> {
+        let mask = mapped_mask((Self::LED_COUNT / 3) as u8, 3, Self::calc_pixel::<I2cError>);
+        self.device.setup_with_enables_blocking(delay, 1, mask)
+    }
+
+    /// Turn off the 48 LEDs wired to the keys, leaving the rest of the frame untouched.
+    pub fn clear_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        let mut changes = [(0, 0); 48];
+        for (change, led) in changes.iter_mut().zip(KEYBOW_2040_LOOKUP.as_flattened()) {
+            change.0 = *led;
+        }
+        // Sorted changes let consecutive LEDs go out in a single write.
+        changes.sort_unstable();
+        self.device.apply_delta_blocking(&changes)
+    }
+
+    /// Set every key to one color in a single write, e.g. to flash the whole board red. The LEDs
+    /// that aren't wired to a key are turned off.
+    pub fn fill_rgb_blocking(&mut self, r: u8, g: u8, b: u8) -> Result<(), Error<I2cError>> {
+        let (r, g, b) = self.channels(r, g, b);
+        let mut frame = [0; LED_COUNT];
+        for [r_led, g_led, b_led] in KEYBOW_2040_LOOKUP {
+            frame[r_led as usize] = r;
+            frame[g_led as usize] = g;
+            frame[b_led as usize] = b;
+        }
+        self.device.all_pixels_blocking(&frame)
+    }
+
+    /// Send the whole canvas to the current frame in a single write, applying the configured
+    /// channel order and gamma to every color.
+    pub fn flush_canvas_blocking(
+        &mut self,
+        canvas: &RgbCanvas<4, 4>,
+    ) -> Result<(), Error<I2cError>> {
+        let frame = canvas.render(|r, g, b| self.channels(r, g, b));
+        self.device.all_pixels_blocking(&frame)
+    }
+
     pub fn pixel_rgb_blocking(
         &mut self,
         x: u8,
@@ -148,10 +854,72 @@ where
         g: u8,
         b: u8,
     ) -> Result<(), Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
         let x = (4 * (3 - x)) + y;
-        self.device.pixel_blocking(Self::calc_pixel(x, 0)?, r)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 1)?, g)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 2)?, b)?;
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device
+            .apply_delta_blocking(&rgb_delta(leds, (r, g, b)))
+    }
+
+    /// Send what was drawn through embedded-graphics since the last flush to the current frame.
+    /// Only the span of LEDs that changed is written, in a single write.
+    #[cfg(feature = "graphics")]
+    pub fn flush_drawing_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        if let Some((first, leds)) = self.draw_buffer.dirty_span() {
+            self.device.pixels_blocking(first, leds)?;
+        }
+        self.draw_buffer.dirty = None;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "keybow_2040")]
+rgb_board! {
+    Keybow2040,
+    /// Enable only the 48 LEDs under the keys, three for each of the 16 keys, disabling the other
+    /// 96 outputs of the chip.
+}
+
+#[cfg(all(feature = "keybow_2040", feature = "graphics"))]
+impl<I2C> OriginDimensions for Keybow2040<I2C> {
+    fn size(&self) -> Size {
+        Size::new(Self::WIDTH as u32, Self::HEIGHT as u32)
+    }
+}
+
+/// Drawing only changes an in-memory copy of the LEDs, with each color going through the
+/// configured gamma and channel order. Send it to the board with
+/// [flush_drawing](Self::flush_drawing_blocking).
+#[cfg(all(feature = "keybow_2040", feature = "graphics"))]
+impl<I2C> DrawTarget for Keybow2040<I2C> {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (u8::try_from(point.x), u8::try_from(point.y)) else {
+                continue;
+            };
+            if x >= Self::WIDTH || y >= Self::HEIGHT {
+                continue;
+            }
+            let rgb = self.channels(color.r(), color.g(), color.b());
+            self.draw_buffer
+                .draw_rgb(4 * (3 - x) + y, rgb, Self::calc_pixel);
+        }
         Ok(())
     }
 }
@@ -169,31 +937,39 @@ where
         g: u8,
         b: u8,
     ) -> Result<(), Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
         let x = (4 * (3 - x)) + y;
-        self.device.pixel(Self::calc_pixel(x, 0)?, r).await?;
-        self.device.pixel(Self::calc_pixel(x, 1)?, g).await?;
-        self.device.pixel(Self::calc_pixel(x, 2)?, b).await?;
-        Ok(())
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device.apply_delta(&rgb_delta(leds, (r, g, b))).await
     }
 }
 
 #[cfg(feature = "led_shim")]
 impl<I2C> LEDShim<I2C> {
-    pub fn configure(i2c: I2C) -> Self {
-        Self {
-            device: IS31FL3731 {
-                i2c,
-                address: 0x75,
-                frame: 0,
-            },
-        }
-    }
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x75;
+    /// The number of RGB pixel columns on the board.
+    pub const WIDTH: u8 = 28;
+    /// The number of RGB pixel rows on the board.
+    pub const HEIGHT: u8 = 1;
+    /// The number of LEDs on the board, three for every RGB pixel.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize * 3;
 
     pub fn calc_pixel<E>(x: u8, y: u8) -> Result<u8, Error<E>> {
-        if x > 28 {
+        if x > 27 {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 3 {
+        if y > 2 {
             return Err(Error::InvalidLocation(y));
         }
         if y == 0 {
@@ -269,6 +1045,18 @@ impl<I2C> LEDShim<I2C> {
 
         Ok(93)
     }
+
+    /// A canvas of the shim's 28 pixels, to be sent with
+    /// [flush_canvas](Self::flush_canvas_blocking).
+    pub const fn canvas() -> RgbCanvas<28, 1> {
+        RgbCanvas::new(|x, _| {
+            // The canvas only asks for x below 28 and channels 0 to 2, which are all wired.
+            let led = |channel| {
+                Self::calc_pixel::<()>(x, channel).expect("every canvas pixel is on the shim")
+            };
+            [led(0), led(1), led(2)]
+        })
+    }
 }
 
 #[cfg(feature = "led_shim")]
@@ -276,6 +1064,12 @@ impl<I2C, I2cError> LEDShim<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
+    /// Setup the display. Only frame 0 is used by this board so only that frame is cleared,
+    /// which is much faster than the full [setup](IS31FL3731::setup_blocking).
+    pub fn setup_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2cError>> {
+        self.device.setup_frames_blocking(delay, 1)
+    }
+
     pub fn pixel_rgb_blocking(
         &mut self,
         x: u8,
@@ -283,9 +1077,101 @@ where
         g: u8,
         b: u8,
     ) -> Result<(), Error<I2cError>> {
-        self.device.pixel_blocking(Self::calc_pixel(x, 0)?, r)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 1)?, g)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 2)?, b)?;
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device
+            .apply_delta_blocking(&rgb_delta(leds, (r, g, b)))
+    }
+
+    /// Send the whole buffer to the current frame in a single write.
+    pub fn flush_blocking(&mut self, buffer: &LEDShimBuffer) -> Result<(), Error<I2cError>> {
+        self.device
+            .all_pixels_blocking(&buffer.render(Self::calc_pixel)?)
+    }
+
+    /// Fill the shim with a rainbow running along its length, shifted by `phase`. Calling this in
+    /// a loop with an incrementing phase makes the rainbow flow.
+    pub fn rainbow_step_blocking(&mut self, phase: u8) -> Result<(), Error<I2cError>> {
+        let mut buffer = LEDShimBuffer::new();
+        for x in 0..28 {
+            let hue = phase.wrapping_add((x * 256 / 28) as u8);
+            let (r, g, b) = hsv_to_rgb(hue, 255, 255);
+            let (r, g, b) = self.channels(r, g, b);
+            buffer.data[x] = [r, g, b];
+        }
+        self.flush_blocking(&buffer)
+    }
+
+    /// Set every pixel to one color in a single write, e.g. to flash the whole shim red.
+    pub fn fill_rgb_blocking(&mut self, r: u8, g: u8, b: u8) -> Result<(), Error<I2cError>> {
+        let (r, g, b) = self.channels(r, g, b);
+        self.flush_blocking(&LEDShimBuffer {
+            data: [[r, g, b]; 28],
+        })
+    }
+
+    /// Send the whole canvas to the current frame in a single write, applying the configured
+    /// channel order and gamma to every color.
+    pub fn flush_canvas_blocking(
+        &mut self,
+        canvas: &RgbCanvas<28, 1>,
+    ) -> Result<(), Error<I2cError>> {
+        let frame = canvas.render(|r, g, b| self.channels(r, g, b));
+        self.device.all_pixels_blocking(&frame)
+    }
+
+    /// Send what was drawn through embedded-graphics since the last flush to the current frame.
+    /// Only the span of LEDs that changed is written, in a single write.
+    #[cfg(feature = "graphics")]
+    pub fn flush_drawing_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        if let Some((first, leds)) = self.draw_buffer.dirty_span() {
+            self.device.pixels_blocking(first, leds)?;
+        }
+        self.draw_buffer.dirty = None;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "led_shim")]
+rgb_board! {
+    LEDShim,
+    /// Enable only the 84 LEDs of the shim, three for each of its 28 pixels, disabling the other 60
+    /// outputs of the chip.
+}
+
+#[cfg(all(feature = "led_shim", feature = "graphics"))]
+impl<I2C> OriginDimensions for LEDShim<I2C> {
+    fn size(&self) -> Size {
+        Size::new(Self::WIDTH as u32, Self::HEIGHT as u32)
+    }
+}
+
+/// Drawing only changes an in-memory copy of the LEDs, with each color going through the
+/// configured gamma and channel order. Send it to the board with
+/// [flush_drawing](Self::flush_drawing_blocking).
+#[cfg(all(feature = "led_shim", feature = "graphics"))]
+impl<I2C> DrawTarget for LEDShim<I2C> {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (u8::try_from(point.x), u8::try_from(point.y)) else {
+                continue;
+            };
+            if x >= Self::WIDTH || y >= Self::HEIGHT {
+                continue;
+            }
+            let rgb = self.channels(color.r(), color.g(), color.b());
+            self.draw_buffer.draw_rgb(x, rgb, Self::calc_pixel);
+        }
         Ok(())
     }
 }
@@ -296,86 +1182,130 @@ where
     I2C: embedded_hal_async::i2c::I2c<Error = I2cError>,
 {
     pub async fn pixel_rgb(&mut self, x: u8, r: u8, g: u8, b: u8) -> Result<(), Error<I2cError>> {
-        self.device.pixel(Self::calc_pixel(x, 0)?, r).await?;
-        self.device.pixel(Self::calc_pixel(x, 1)?, g).await?;
-        self.device.pixel(Self::calc_pixel(x, 2)?, b).await?;
-        Ok(())
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device.apply_delta(&rgb_delta(leds, (r, g, b))).await
     }
 }
 
+#[cfg(feature = "matrix")]
+impl<I2C> Matrix<I2C> {
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x74;
+    /// The number of columns on the board.
+    pub const WIDTH: u8 = 16;
+    /// The number of rows on the board.
+    pub const HEIGHT: u8 = 9;
+    /// The number of LEDs on the board.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
+}
+
 #[cfg(feature = "matrix")]
 impl<I2C, I2cError> Matrix<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
-    pub fn configure(i2c: I2C) -> Self {
-        Self {
-            device: IS31FL3731 {
-                i2c,
-                address: 0x74,
-                frame: 0,
-            },
-        }
-    }
-
     pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
-        if x > 16 {
+        if x >= Self::WIDTH {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 9 {
+        if y >= Self::HEIGHT {
             return Err(Error::InvalidLocation(y));
         }
         Ok(x + y * 16)
     }
+
+    /// Send the whole buffer to the current frame in a single write.
+    pub fn flush_blocking(&mut self, buffer: &MatrixBuffer) -> Result<(), Error<I2cError>> {
+        self.device
+            .all_pixels_blocking(&buffer.render(Self::calc_pixel)?)
+    }
+
+    /// Draw a bar graph in a single write, lighting each column from the bottom up to its entry
+    /// in `heights` and turning the rest off. Heights above 9 are drawn as 9. This is the building
+    /// block for spectrum analyzers and VU meters.
+    pub fn draw_bars_blocking(
+        &mut self,
+        heights: &[u8; 16],
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        let mut buffer = MatrixBuffer::new();
+        for (column, height) in buffer.data.iter_mut().zip(heights) {
+            let height = (*height).min(Self::HEIGHT) as usize;
+            column[Self::HEIGHT as usize - height..].fill(brightness);
+        }
+        self.flush_blocking(&buffer)
+    }
 }
 
+#[cfg(feature = "matrix")]
+mono_board! {
+    Matrix,
+    /// Every one of the chip's 144 outputs drives an LED of the matrix, so this enables all of
+    /// them. It is only needed to undo an earlier enable mask.
+}
+
+/// The LED of each color channel of every [RGBMatrix5x5] pixel.
+#[cfg(feature = "rgb_matrix_5x5")]
+const RGB_MATRIX_5X5_LOOKUP: [[u8; 3]; 25] = [
+    [118, 69, 85],
+    [117, 68, 101],
+    [116, 84, 100],
+    [115, 83, 99],
+    [114, 82, 98],
+    [132, 19, 35],
+    [133, 20, 36],
+    [134, 21, 37],
+    [112, 80, 96],
+    [113, 81, 97],
+    [131, 18, 34],
+    [130, 17, 50],
+    [129, 33, 49],
+    [128, 32, 48],
+    [127, 47, 63],
+    [125, 28, 44],
+    [124, 27, 43],
+    [123, 26, 42],
+    [122, 25, 58],
+    [121, 41, 57],
+    [126, 29, 45],
+    [15, 95, 111],
+    [8, 89, 105],
+    [9, 90, 106],
+    [10, 91, 107],
+];
+#[cfg(feature = "rgb_matrix_5x5")]
+const _: () = assert!(valid_lookup(&RGB_MATRIX_5X5_LOOKUP));
+
 #[cfg(feature = "rgb_matrix_5x5")]
 impl<I2C> RGBMatrix5x5<I2C> {
-    pub fn configure(i2c: I2C) -> Self {
-        Self {
-            device: IS31FL3731 {
-                i2c,
-                address: 0x75,
-                frame: 0,
-            },
-        }
-    }
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x75;
+    /// The number of RGB pixel columns on the board.
+    pub const WIDTH: u8 = 5;
+    /// The number of RGB pixel rows on the board.
+    pub const HEIGHT: u8 = 5;
+    /// The number of LEDs on the board, three for every RGB pixel.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize * 3;
 
     pub fn calc_pixel<E>(x: u8, y: u8) -> Result<u8, Error<E>> {
-        if x > 25 {
+        if x > 24 {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 3 {
+        if y > 2 {
             return Err(Error::InvalidLocation(y));
         }
-        let lookup = [
-            [118, 69, 85],
-            [117, 68, 101],
-            [116, 84, 100],
-            [115, 83, 99],
-            [114, 82, 98],
-            [132, 19, 35],
-            [133, 20, 36],
-            [134, 21, 37],
-            [112, 80, 96],
-            [113, 81, 97],
-            [131, 18, 34],
-            [130, 17, 50],
-            [129, 33, 49],
-            [128, 32, 48],
-            [127, 47, 63],
-            [125, 28, 44],
-            [124, 27, 43],
-            [123, 26, 42],
-            [122, 25, 58],
-            [121, 41, 57],
-            [126, 29, 45],
-            [15, 95, 111],
-            [8, 89, 105],
-            [9, 90, 106],
-            [10, 91, 107],
-        ];
-        Ok(lookup[x as usize][y as usize])
+        Ok(RGB_MATRIX_5X5_LOOKUP[x as usize][y as usize])
+    }
+
+    /// A canvas of the board's 5 by 5 pixels, to be sent with
+    /// [flush_canvas](Self::flush_canvas_blocking).
+    pub const fn canvas() -> RgbCanvas<5, 5> {
+        RgbCanvas::new(|x, y| RGB_MATRIX_5X5_LOOKUP[(x + y * 5) as usize])
     }
 }
 
@@ -384,6 +1314,12 @@ impl<I2C, I2cError> RGBMatrix5x5<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
+    /// Setup the display. Only frame 0 is used by this board so only that frame is cleared,
+    /// which is much faster than the full [setup](IS31FL3731::setup_blocking).
+    pub fn setup_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2cError>> {
+        self.device.setup_frames_blocking(delay, 1)
+    }
+
     pub fn pixel_rgb_blocking(
         &mut self,
         x: u8,
@@ -392,10 +1328,110 @@ where
         g: u8,
         b: u8,
     ) -> Result<(), Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
         let x = x + y * 5;
-        self.device.pixel_blocking(Self::calc_pixel(x, 0)?, r)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 1)?, g)?;
-        self.device.pixel_blocking(Self::calc_pixel(x, 2)?, b)?;
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device
+            .apply_delta_blocking(&rgb_delta(leds, (r, g, b)))
+    }
+
+    /// Send the whole buffer to the current frame in a single write.
+    pub fn flush_blocking(&mut self, buffer: &RGBMatrix5x5Buffer) -> Result<(), Error<I2cError>> {
+        self.device
+            .all_pixels_blocking(&buffer.render(Self::calc_pixel)?)
+    }
+
+    /// Fill the matrix with a diagonal rainbow, shifted by `phase`. Calling this in a loop with an
+    /// incrementing phase makes the rainbow flow.
+    pub fn rainbow_step_blocking(&mut self, phase: u8) -> Result<(), Error<I2cError>> {
+        let mut buffer = RGBMatrix5x5Buffer::new();
+        for y in 0..5 {
+            for x in 0..5 {
+                let hue = phase.wrapping_add(((x + y) * 256 / 9) as u8);
+                let (r, g, b) = hsv_to_rgb(hue, 255, 255);
+                let (r, g, b) = self.channels(r, g, b);
+                buffer.data[x + y * 5] = [r, g, b];
+            }
+        }
+        self.flush_blocking(&buffer)
+    }
+
+    /// Set every pixel to one color in a single write, e.g. to flash the whole matrix red.
+    pub fn fill_rgb_blocking(&mut self, r: u8, g: u8, b: u8) -> Result<(), Error<I2cError>> {
+        let (r, g, b) = self.channels(r, g, b);
+        self.flush_blocking(&RGBMatrix5x5Buffer {
+            data: [[r, g, b]; 25],
+        })
+    }
+
+    /// Send the whole canvas to the current frame in a single write, applying the configured
+    /// channel order and gamma to every color.
+    pub fn flush_canvas_blocking(
+        &mut self,
+        canvas: &RgbCanvas<5, 5>,
+    ) -> Result<(), Error<I2cError>> {
+        let frame = canvas.render(|r, g, b| self.channels(r, g, b));
+        self.device.all_pixels_blocking(&frame)
+    }
+
+    /// Send what was drawn through embedded-graphics since the last flush to the current frame.
+    /// Only the span of LEDs that changed is written, in a single write.
+    #[cfg(feature = "graphics")]
+    pub fn flush_drawing_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        if let Some((first, leds)) = self.draw_buffer.dirty_span() {
+            self.device.pixels_blocking(first, leds)?;
+        }
+        self.draw_buffer.dirty = None;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rgb_matrix_5x5")]
+rgb_board! {
+    RGBMatrix5x5,
+    /// Enable only the 75 LEDs of the matrix, three for each of its 25 pixels, disabling the other
+    /// 69 outputs of the chip.
+}
+
+#[cfg(all(feature = "rgb_matrix_5x5", feature = "graphics"))]
+impl<I2C> OriginDimensions for RGBMatrix5x5<I2C> {
+    fn size(&self) -> Size {
+        Size::new(Self::WIDTH as u32, Self::HEIGHT as u32)
+    }
+}
+
+/// Drawing only changes an in-memory copy of the LEDs, with each color going through the
+/// configured gamma and channel order. Send it to the board with
+/// [flush_drawing](Self::flush_drawing_blocking).
+#[cfg(all(feature = "rgb_matrix_5x5", feature = "graphics"))]
+impl<I2C> DrawTarget for RGBMatrix5x5<I2C> {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (u8::try_from(point.x), u8::try_from(point.y)) else {
+                continue;
+            };
+            if x >= Self::WIDTH || y >= Self::HEIGHT {
+                continue;
+            }
+            let rgb = self.channels(color.r(), color.g(), color.b());
+            self.draw_buffer.draw_rgb(x + y * 5, rgb, Self::calc_pixel);
+        }
         Ok(())
     }
 }
@@ -413,45 +1449,573 @@ where
         g: u8,
         b: u8,
     ) -> Result<(), Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
         let x = x + y * 5;
-        self.device.pixel(Self::calc_pixel(x, 0)?, r).await?;
-        self.device.pixel(Self::calc_pixel(x, 1)?, g).await?;
-        self.device.pixel(Self::calc_pixel(x, 2)?, b).await?;
-        Ok(())
+        let (r, g, b) = self.channels(r, g, b);
+        let leds = [
+            Self::calc_pixel(x, 0)?,
+            Self::calc_pixel(x, 1)?,
+            Self::calc_pixel(x, 2)?,
+        ];
+        self.device.apply_delta(&rgb_delta(leds, (r, g, b))).await
+    }
+
+    /// Send the whole buffer to the current frame in a single write.
+    pub async fn flush(&mut self, buffer: &RGBMatrix5x5Buffer) -> Result<(), Error<I2cError>> {
+        self.device
+            .all_pixels(&buffer.render(Self::calc_pixel)?)
+            .await
+    }
+
+    /// Send the whole canvas to the current frame in a single write, applying the configured
+    /// channel order and gamma to every color.
+    pub async fn flush_canvas(&mut self, canvas: &RgbCanvas<5, 5>) -> Result<(), Error<I2cError>> {
+        let frame = canvas.render(|r, g, b| self.channels(r, g, b));
+        self.device.all_pixels(&frame).await
     }
 }
 
+#[cfg(feature = "scroll_phat_hd")]
+impl<I2C> ScrollPhatHD<I2C> {
+    /// The i2c address the board is strapped to.
+    pub const ADDRESS: u8 = 0x74;
+    /// The number of columns on the board.
+    pub const WIDTH: u8 = 17;
+    /// The number of rows on the board.
+    pub const HEIGHT: u8 = 7;
+    /// The number of LEDs on the board.
+    pub const LED_COUNT: usize = Self::WIDTH as usize * Self::HEIGHT as usize;
+}
+
 #[cfg(feature = "scroll_phat_hd")]
 impl<I2C, I2cError> ScrollPhatHD<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
-    pub fn configure(i2c: I2C) -> Self {
+    pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
+        if x >= Self::WIDTH {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= Self::HEIGHT {
+            return Err(Error::InvalidLocation(y));
+        }
+        Ok(if x <= 8 {
+            (8 - x) * 16 + 6 - y
+        } else {
+            (x - 8) * 16 + y - 8
+        })
+    }
+
+    /// Show a 17 column window of `wide_buf`, a buffer of columns of any width, starting at
+    /// column `x_offset` and wrapping around past its end. Render text or graphics into the wide
+    /// buffer once and increment the offset to scroll it across the display like a ticker. An
+    /// empty buffer clears the display.
+    pub fn scroll_buffer_blocking(
+        &mut self,
+        wide_buf: &[[u8; 7]],
+        x_offset: usize,
+    ) -> Result<(), Error<I2cError>> {
+        let mut frame = [0; LED_COUNT];
+        if !wide_buf.is_empty() {
+            let x_offset = x_offset % wide_buf.len();
+            for x in 0..Self::WIDTH {
+                let column = &wide_buf[(x_offset + x as usize) % wide_buf.len()];
+                for (y, value) in column.iter().enumerate() {
+                    frame[Self::calc_pixel(x, y as u8)? as usize] = *value;
+                }
+            }
+        }
+        self.device.all_pixels_blocking(&frame)
+    }
+}
+
+#[cfg(feature = "scroll_phat_hd")]
+mono_board! {
+    ScrollPhatHD,
+    /// Enable only the 119 LEDs wired to the board, disabling the 25 outputs of the chip that have
+    /// no LED.
+}
+
+/// A single color board that isn't built into the crate, described by a lookup table from
+/// coordinates to LEDs. Entry `x + y * width` of the lookup is the LED at `(x, y)`, so a table can
+/// be shared as a plain const array.
+pub struct GenericMono<I2C> {
+    pub device: IS31FL3731<I2C>,
+    lookup: &'static [u8],
+    width: u8,
+    height: u8,
+}
+
+impl<I2C> GenericMono<I2C> {
+    /// Wrap a board at `address` that is `width` by `height` LEDs, laid out by `lookup`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `lookup` isn't `width * height` or any of its LEDs is 144 or
+    /// above.
+    pub fn new(i2c: I2C, address: u8, width: u8, height: u8, lookup: &'static [u8]) -> Self {
+        assert!(
+            lookup.len() == width as usize * height as usize,
+            "lookup doesn't match the dimensions"
+        );
+        assert!(
+            lookup.iter().all(|led| (*led as usize) < LED_COUNT),
+            "lookup has an LED past the end of the chip"
+        );
+        Self {
+            device: IS31FL3731::new(i2c, address),
+            lookup,
+            width,
+            height,
+        }
+    }
+
+    /// The `(width, height)` of the board.
+    pub fn dimensions(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    /// The number of LEDs on the board.
+    pub fn led_count(&self) -> usize {
+        self.lookup.len()
+    }
+
+    /// Whether `(x, y)` is on the board, i.e. whether [calc_pixel](Self::calc_pixel) will accept
+    /// it.
+    pub fn is_valid(&self, x: u8, y: u8) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn calc_pixel<E>(&self, x: u8, y: u8) -> Result<u8, Error<E>> {
+        if x >= self.width {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= self.height {
+            return Err(Error::InvalidLocation(y));
+        }
+        Ok(self.lookup[x as usize + y as usize * self.width as usize])
+    }
+}
+
+impl<I2C, I2cError> GenericMono<I2C>
+where
+    I2C: I2c<Error = I2cError>,
+{
+    /// Set the brightness of the LED at `(x, y)`. For gamma corrected brightness pass the value
+    /// through [gamma](crate::gamma) first.
+    pub fn pixel_xy_blocking(
+        &mut self,
+        x: u8,
+        y: u8,
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        let led = self.calc_pixel(x, y)?;
+        self.device.pixel_blocking(led, brightness)
+    }
+
+    /// Disable every LED that isn't in the lookup, which saves current and avoids ghosting on the
+    /// unused Charlieplex nodes.
+    pub fn enable_only_mapped_blocking(&mut self) -> Result<(), I2cError> {
+        let mut mask = [0; 18];
+        for led in self.lookup {
+            mask[*led as usize / 8] |= 1 << (led % 8);
+        }
+        self.device.set_enable_mask_blocking(&mask)
+    }
+}
+
+/// An RGB board that isn't built into the crate, described by a lookup table from pixels to the
+/// LEDs of their red, green and blue channels. Entry `x + y * width` of the lookup is the pixel at
+/// `(x, y)`, so a table can be shared as a plain const array.
+pub struct GenericRgb<I2C> {
+    pub device: IS31FL3731<I2C>,
+    lookup: &'static [[u8; 3]],
+    width: u8,
+    height: u8,
+}
+
+impl<I2C> GenericRgb<I2C> {
+    /// Wrap a board at `address` that is `width` by `height` RGB pixels, laid out by `lookup`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `lookup` isn't `width * height` or any of its LEDs is 144 or
+    /// above.
+    pub fn new(i2c: I2C, address: u8, width: u8, height: u8, lookup: &'static [[u8; 3]]) -> Self {
+        assert!(
+            lookup.len() == width as usize * height as usize,
+            "lookup doesn't match the dimensions"
+        );
+        assert!(
+            lookup
+                .iter()
+                .flatten()
+                .all(|led| (*led as usize) < LED_COUNT),
+            "lookup has an LED past the end of the chip"
+        );
         Self {
-            device: IS31FL3731 {
-                i2c,
-                address: 0x74,
-                frame: 0,
-            },
+            device: IS31FL3731::new(i2c, address),
+            lookup,
+            width,
+            height,
         }
     }
 
-    pub fn calc_pixel(x: u8, y: u8) -> Result<u8, Error<I2cError>> {
-        if x > 17 {
+    /// The `(width, height)` of the board in RGB pixels.
+    pub fn dimensions(&self) -> (u8, u8) {
+        (self.width, self.height)
+    }
+
+    /// The number of LEDs on the board, three for every RGB pixel.
+    pub fn led_count(&self) -> usize {
+        self.lookup.len() * 3
+    }
+
+    /// Whether `x` is a pixel on the board and `y` is one of its 3 color channels, the coordinate
+    /// space used by [calc_pixel](Self::calc_pixel).
+    pub fn is_valid(&self, x: u8, y: u8) -> bool {
+        (x as usize) < self.lookup.len() && y < 3
+    }
+
+    pub fn calc_pixel<E>(&self, x: u8, y: u8) -> Result<u8, Error<E>> {
+        if x as usize >= self.lookup.len() {
             return Err(Error::InvalidLocation(x));
         }
-        if y > 7 {
+        if y > 2 {
             return Err(Error::InvalidLocation(y));
         }
-        let mut x = x;
-        let mut y = y;
-        if x <= 8 {
-            x = 8 - x;
-            y = 6 - y;
-        } else {
-            x -= 8;
-            y -= 8;
+        Ok(self.lookup[x as usize][y as usize])
+    }
+}
+
+impl<I2C, I2cError> GenericRgb<I2C>
+where
+    I2C: I2c<Error = I2cError>,
+{
+    pub fn pixel_rgb_blocking(
+        &mut self,
+        x: u8,
+        y: u8,
+        r: u8,
+        g: u8,
+        b: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if x >= self.width {
+            return Err(Error::InvalidLocation(x));
+        }
+        if y >= self.height {
+            return Err(Error::InvalidLocation(y));
+        }
+        let leds = self.lookup[x as usize + y as usize * self.width as usize];
+        self.device
+            .apply_delta_blocking(&rgb_delta(leds, (r, g, b)))
+    }
+
+    /// Disable every LED that isn't in the lookup, which saves current and avoids ghosting on the
+    /// unused Charlieplex nodes.
+    pub fn enable_only_mapped_blocking(&mut self) -> Result<(), I2cError> {
+        let mut mask = [0; 18];
+        for led in self.lookup.iter().flatten() {
+            mask[*led as usize / 8] |= 1 << (led % 8);
+        }
+        self.device.set_enable_mask_blocking(&mask)
+    }
+}
+
+#[cfg(all(
+    test,
+    any(
+        feature = "charlie_bonnet",
+        feature = "charlie_wing",
+        feature = "eleven_7",
+        feature = "keybow_2040",
+        feature = "led_shim",
+        feature = "matrix",
+        feature = "rgb_matrix_5x5",
+        feature = "scroll_phat_hd"
+    )
+))]
+mod tests {
+    use super::*;
+    use crate::mock::MockI2c;
+
+    /// Check that `calc_pixel` maps every coordinate of a `width` by `height` board to its own LED
+    /// and rejects the first coordinate past each edge.
+    fn assert_one_to_one<E: core::fmt::Debug>(
+        width: u8,
+        height: u8,
+        calc_pixel: fn(u8, u8) -> Result<u8, Error<E>>,
+    ) {
+        let mut seen = [false; LED_COUNT];
+        for x in 0..width {
+            for y in 0..height {
+                let led = calc_pixel(x, y).expect("coordinate on the board rejected");
+                assert!(!seen[led as usize], "({}, {}) reuses LED {}", x, y, led);
+                seen[led as usize] = true;
+            }
+        }
+        assert!(matches!(calc_pixel(width, 0), Err(Error::InvalidLocation(x)) if x == width));
+        assert!(matches!(calc_pixel(0, height), Err(Error::InvalidLocation(y)) if y == height));
+    }
+
+    /// The only LED of frame 0 that is lit.
+    fn lit_led(i2c: &MockI2c) -> u8 {
+        let frame = i2c.frame(0);
+        let mut lit = (0..LED_COUNT).filter(|&led| frame[led] != 0);
+        let led = lit.next().expect("no LED is lit");
+        assert_eq!(lit.next(), None, "more than one LED is lit");
+        led as u8
+    }
+
+    #[cfg(feature = "keybow_2040")]
+    #[test]
+    fn keybow_2040_bounds() {
+        assert!(Keybow2040::<MockI2c>::calc_pixel::<()>(15, 2).is_ok());
+        assert!(matches!(
+            Keybow2040::<MockI2c>::calc_pixel::<()>(16, 0),
+            Err(Error::InvalidLocation(16))
+        ));
+        assert!(matches!(
+            Keybow2040::<MockI2c>::calc_pixel::<()>(0, 3),
+            Err(Error::InvalidLocation(3))
+        ));
+        let mut keybow = Keybow2040::configure(MockI2c::new());
+        assert!(keybow.pixel_rgb_blocking(3, 3, 1, 2, 3).is_ok());
+        assert!(matches!(
+            keybow.pixel_rgb_blocking(4, 0, 1, 2, 3),
+            Err(Error::InvalidLocation(4))
+        ));
+        assert!(matches!(
+            keybow.pixel_rgb_blocking(0, 4, 1, 2, 3),
+            Err(Error::InvalidLocation(4))
+        ));
+    }
+
+    #[cfg(feature = "keybow_2040")]
+    #[test]
+    fn keybow_2040_setup_enables_only_the_keys() {
+        let mut keybow = Keybow2040::configure(MockI2c::new());
+        keybow.setup_blocking(&mut crate::mock::NoDelay).unwrap();
+        let mut plain = IS31FL3731::new(MockI2c::new(), Keybow2040::<MockI2c>::ADDRESS);
+        plain
+            .setup_frames_blocking(&mut crate::mock::NoDelay, 1)
+            .unwrap();
+        assert_eq!(
+            keybow.device.i2c.transactions.len(),
+            plain.i2c.transactions.len()
+        );
+        let enabled = (0..18)
+            .map(|col| {
+                keybow
+                    .device
+                    .i2c
+                    .register(0, crate::addresses::ENABLE_OFFSET + col)
+                    .count_ones()
+            })
+            .sum::<u32>();
+        assert_eq!(enabled, 48);
+    }
+
+    #[cfg(feature = "rgb_matrix_5x5")]
+    #[test]
+    fn rgb_matrix_5x5_bounds() {
+        assert_eq!(
+            RGBMatrix5x5::<MockI2c>::calc_pixel::<()>(24, 2).ok(),
+            Some(RGB_MATRIX_5X5_LOOKUP[24][2])
+        );
+        assert!(matches!(
+            RGBMatrix5x5::<MockI2c>::calc_pixel::<()>(25, 0),
+            Err(Error::InvalidLocation(25))
+        ));
+        let mut matrix = RGBMatrix5x5::configure(MockI2c::new());
+        matrix.pixel_rgb_blocking(4, 4, 1, 2, 3).unwrap();
+        let frame = matrix.device.i2c.frame(0);
+        for (led, value) in RGB_MATRIX_5X5_LOOKUP[24].into_iter().zip([1, 2, 3]) {
+            assert_eq!(frame[led as usize], value);
+        }
+        assert!(matches!(
+            matrix.pixel_rgb_blocking(5, 0, 1, 2, 3),
+            Err(Error::InvalidLocation(5))
+        ));
+        assert!(matches!(
+            matrix.pixel_rgb_blocking(0, 5, 1, 2, 3),
+            Err(Error::InvalidLocation(5))
+        ));
+    }
+
+    #[cfg(feature = "charlie_bonnet")]
+    #[test]
+    fn charlie_bonnet_mapping_is_one_to_one() {
+        assert_one_to_one(
+            CharlieBonnet::<MockI2c>::WIDTH,
+            CharlieBonnet::<MockI2c>::HEIGHT,
+            CharlieBonnet::<MockI2c>::calc_pixel,
+        );
+    }
+
+    #[cfg(feature = "charlie_wing")]
+    #[test]
+    fn charlie_wing_mapping_is_one_to_one() {
+        assert_one_to_one(
+            CharlieWing::<MockI2c>::WIDTH,
+            CharlieWing::<MockI2c>::HEIGHT,
+            CharlieWing::<MockI2c>::calc_pixel,
+        );
+    }
+
+    #[cfg(feature = "eleven_7")]
+    #[test]
+    fn eleven_7_mapping_is_one_to_one() {
+        assert_one_to_one(
+            Eleven7::<MockI2c>::WIDTH,
+            Eleven7::<MockI2c>::HEIGHT,
+            Eleven7::<MockI2c>::calc_pixel,
+        );
+    }
+
+    #[cfg(feature = "keybow_2040")]
+    #[test]
+    fn keybow_2040_mapping_is_one_to_one() {
+        assert_one_to_one(16, 3, Keybow2040::<MockI2c>::calc_pixel::<()>);
+    }
+
+    #[cfg(feature = "matrix")]
+    #[test]
+    fn matrix_mapping_is_one_to_one() {
+        assert_one_to_one(
+            Matrix::<MockI2c>::WIDTH,
+            Matrix::<MockI2c>::HEIGHT,
+            Matrix::<MockI2c>::calc_pixel,
+        );
+    }
+
+    #[cfg(feature = "rgb_matrix_5x5")]
+    #[test]
+    fn rgb_matrix_5x5_mapping_is_one_to_one() {
+        assert_one_to_one(25, 3, RGBMatrix5x5::<MockI2c>::calc_pixel::<()>);
+    }
+
+    #[cfg(feature = "scroll_phat_hd")]
+    #[test]
+    fn scroll_phat_hd_mapping_is_one_to_one() {
+        assert_one_to_one(
+            ScrollPhatHD::<MockI2c>::WIDTH,
+            ScrollPhatHD::<MockI2c>::HEIGHT,
+            ScrollPhatHD::<MockI2c>::calc_pixel,
+        );
+    }
+
+    #[cfg(feature = "led_shim")]
+    #[test]
+    fn led_shim_bounds_and_mapping() {
+        assert_one_to_one(28, 3, LEDShim::<MockI2c>::calc_pixel::<()>);
+    }
+
+    #[cfg(feature = "charlie_bonnet")]
+    #[test]
+    fn charlie_bonnet_origin_is_top_left() {
+        let mut bonnet = CharlieBonnet::configure(MockI2c::new());
+        bonnet.pixel_xy_blocking(0, 0, 1).unwrap();
+        assert_eq!(lit_led(&bonnet.device.i2c), 23);
+        bonnet.device.clear_blocking().unwrap();
+        bonnet.pixel_xy_blocking(15, 7, 1).unwrap();
+        assert_eq!(lit_led(&bonnet.device.i2c), 136);
+    }
+
+    #[cfg(feature = "charlie_wing")]
+    #[test]
+    fn charlie_wing_origin_is_top_left() {
+        let mut wing = CharlieWing::configure(MockI2c::new());
+        wing.pixel_xy_blocking(0, 0, 1).unwrap();
+        assert_eq!(lit_led(&wing.device.i2c), 7);
+        wing.device.clear_blocking().unwrap();
+        wing.pixel_xy_blocking(14, 6, 1).unwrap();
+        assert_eq!(lit_led(&wing.device.i2c), 30);
+    }
+
+    #[cfg(feature = "eleven_7")]
+    #[test]
+    fn eleven_7_origin_is_top_left() {
+        let mut eleven = Eleven7::configure(MockI2c::new());
+        eleven.pixel_xy_blocking(0, 0, 1).unwrap();
+        assert_eq!(lit_led(&eleven.device.i2c), 6);
+        eleven.device.clear_blocking().unwrap();
+        eleven.pixel_xy_blocking(10, 6, 1).unwrap();
+        assert_eq!(lit_led(&eleven.device.i2c), 72);
+    }
+
+    #[cfg(feature = "matrix")]
+    #[test]
+    fn matrix_origin_is_top_left() {
+        let mut matrix = Matrix::configure(MockI2c::new());
+        matrix.pixel_xy_blocking(0, 0, 1).unwrap();
+        assert_eq!(lit_led(&matrix.device.i2c), 0);
+        matrix.device.clear_blocking().unwrap();
+        matrix.pixel_xy_blocking(15, 8, 1).unwrap();
+        assert_eq!(lit_led(&matrix.device.i2c), 143);
+    }
+
+    #[cfg(feature = "scroll_phat_hd")]
+    #[test]
+    fn scroll_phat_hd_origin_is_top_left() {
+        let mut scroll = ScrollPhatHD::configure(MockI2c::new());
+        scroll.pixel_xy_blocking(0, 0, 1).unwrap();
+        assert_eq!(lit_led(&scroll.device.i2c), 134);
+        scroll.device.clear_blocking().unwrap();
+        scroll.pixel_xy_blocking(16, 6, 1).unwrap();
+        assert_eq!(lit_led(&scroll.device.i2c), 126);
+    }
+
+    #[cfg(feature = "keybow_2040")]
+    #[test]
+    fn keybow_2040_origin_is_top_left() {
+        let mut keybow = Keybow2040::configure(MockI2c::new());
+        keybow.pixel_rgb_blocking(0, 0, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&keybow.device.i2c), KEYBOW_2040_LOOKUP[12][0]);
+        keybow.clear_blocking().unwrap();
+        keybow.pixel_rgb_blocking(3, 3, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&keybow.device.i2c), KEYBOW_2040_LOOKUP[3][0]);
+    }
+
+    #[cfg(feature = "led_shim")]
+    #[test]
+    fn led_shim_origin_is_left() {
+        let mut shim = LEDShim::configure(MockI2c::new());
+        shim.pixel_rgb_blocking(0, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&shim.device.i2c), 118);
+        shim.device.clear_blocking().unwrap();
+        shim.pixel_rgb_blocking(27, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&shim.device.i2c), 13);
+    }
+
+    #[cfg(feature = "rgb_matrix_5x5")]
+    #[test]
+    fn rgb_matrix_5x5_origin_is_top_left() {
+        let mut matrix = RGBMatrix5x5::configure(MockI2c::new());
+        matrix.pixel_rgb_blocking(0, 0, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&matrix.device.i2c), RGB_MATRIX_5X5_LOOKUP[0][0]);
+        matrix.device.clear_blocking().unwrap();
+        matrix.pixel_rgb_blocking(4, 4, 1, 0, 0).unwrap();
+        assert_eq!(lit_led(&matrix.device.i2c), RGB_MATRIX_5X5_LOOKUP[24][0]);
+    }
+
+    #[cfg(feature = "matrix")]
+    #[test]
+    fn orientation_moves_the_origin() {
+        let dimensions = Matrix::<MockI2c>::dimensions();
+        for (orientation, led) in [
+            (Orientation::Normal, 0),
+            (Orientation::Rotate180, 143),
+            (Orientation::FlipX, 15),
+            (Orientation::FlipY, 128),
+        ] {
+            let (x, y) = orientation.apply(0, 0, dimensions);
+            assert_eq!(Matrix::<MockI2c>::calc_pixel(x, y).ok(), Some(led));
         }
-        Ok(x * 16 + y)
     }
 }
@@ -0,0 +1,61 @@
+use crate::{Error, IS31FL3731, LED_COUNT};
+use embedded_hal::i2c::I2c;
+
+/// Temporal dithering for 12-bit brightness on the 8-bit PWM of the chip. Each LED is given a
+/// 12-bit level with [set_pixel_dithered](Self::set_pixel_dithered) and every call to
+/// [tick](Self::tick_blocking) writes the current frame, alternating each LED between the two
+/// 8-bit values around its level so that on average it shows the level in between. Call `tick`
+/// at a high, steady rate (a few hundred times a second) to avoid visible flicker. This makes
+/// slow fades near black much smoother.
+pub struct Dither<I2C> {
+    pub device: IS31FL3731<I2C>,
+    levels: [u16; LED_COUNT],
+    accumulators: [u8; LED_COUNT],
+}
+
+impl<I2C> Dither<I2C> {
+    /// Wrap an already setup device with every LED at level 0.
+    pub fn new(device: IS31FL3731<I2C>) -> Self {
+        Self {
+            device,
+            levels: [0; LED_COUNT],
+            accumulators: [0; LED_COUNT],
+        }
+    }
+}
+
+impl<I2C, I2cError> Dither<I2C>
+where
+    I2C: I2c<Error = I2cError>,
+{
+    /// Set the 12-bit level of a specific LED, from 0 to 4095. Larger values are treated as 4095.
+    /// The LED changes on the next [tick](Self::tick_blocking). If the LED is out of range then
+    /// the function will return an error of [InvalidLocation](Error::InvalidLocation).
+    pub fn set_pixel_dithered(&mut self, led: u8, brightness: u16) -> Result<(), Error<I2cError>> {
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
+        }
+        self.levels[led as usize] = brightness.min(4095);
+        Ok(())
+    }
+
+    /// Write the next dithered picture to the current frame in a single write.
+    pub fn tick_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        let mut frame = [0; LED_COUNT];
+        for ((value, level), accumulator) in frame
+            .iter_mut()
+            .zip(self.levels)
+            .zip(self.accumulators.iter_mut())
+        {
+            // The top 8 bits are the PWM value and the low 4 bits how many ticks out of every 16
+            // should show the next value up.
+            *accumulator += (level & 0x0F) as u8;
+            *value = (level >> 4) as u8;
+            if *accumulator >= 16 {
+                *accumulator -= 16;
+                *value = value.saturating_add(1);
+            }
+        }
+        self.device.all_pixels_blocking(&frame)
+    }
+}
@@ -0,0 +1,84 @@
+use crate::{BlinkSetting, Error, IS31FL3731, LED_COUNT};
+use embedded_hal::i2c::I2c;
+
+/// Tear-free animation using frames 0 and 1 of the chip. Drawing always goes to the hidden back
+/// frame and [present](Self::present_blocking) swaps it to the front, so a half drawn picture is
+/// never shown.
+///
+/// Note that after presenting, the new back frame still holds the picture from before the
+/// previous present. Redraw it completely or use [copy_frame](IS31FL3731::copy_frame_blocking)
+/// if you need to draw incrementally.
+pub struct DoubleBuffer<I2C> {
+    pub device: IS31FL3731<I2C>,
+    front: u8,
+}
+
+impl<I2C> DoubleBuffer<I2C> {
+    /// Wrap an already setup device. Frame 0 is treated as the front frame.
+    pub fn new(device: IS31FL3731<I2C>) -> Self {
+        Self { device, front: 0 }
+    }
+
+    /// The frame currently being displayed.
+    pub fn front(&self) -> u8 {
+        self.front
+    }
+
+    /// The hidden frame that drawing goes to.
+    pub fn back(&self) -> u8 {
+        1 - self.front
+    }
+}
+
+impl<I2C, I2cError> DoubleBuffer<I2C>
+where
+    I2C: I2c<Error = I2cError>,
+{
+    /// Set the brightness of a specific LED in the back frame. If the LED is out of range then the
+    /// function will return an error of [InvalidLocation](Error::InvalidLocation).
+    pub fn pixel_blocking(&mut self, led: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        self.device
+            .pixel_in_frame_blocking(self.back(), led, brightness)
+    }
+
+    /// Fill the back frame with a single brightness.
+    pub fn fill_blocking(&mut self, brightness: u8) -> Result<(), I2cError> {
+        self.device
+            .fill_blocking(brightness, BlinkSetting::Leave, self.back())
+    }
+
+    /// Set the brightness of all 144 LEDs of the back frame at once.
+    pub fn all_pixels_blocking(&mut self, buf: &[u8; LED_COUNT]) -> Result<(), Error<I2cError>> {
+        self.device.render_into_blocking(self.back(), buf)
+    }
+
+    /// Display the back frame, making the old front frame the new back frame.
+    pub fn present_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        self.device.display_frame_blocking(self.back())?;
+        self.front = self.back();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockI2c;
+
+    #[test]
+    fn inverted_pixel_matches_fill_and_all_pixels() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        device.set_inverted(true);
+        let mut buffer = DoubleBuffer::new(device);
+        buffer.fill_blocking(10).unwrap();
+        buffer.pixel_blocking(3, 10).unwrap();
+        assert_eq!(buffer.device.i2c.frame(buffer.back()), [245; LED_COUNT]);
+        buffer.all_pixels_blocking(&[20; LED_COUNT]).unwrap();
+        buffer.pixel_blocking(3, 20).unwrap();
+        assert_eq!(buffer.device.i2c.frame(buffer.back()), [235; LED_COUNT]);
+        assert!(matches!(
+            buffer.pixel_blocking(144, 0),
+            Err(Error::InvalidLocation(144))
+        ));
+    }
+}
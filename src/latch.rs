@@ -0,0 +1,83 @@
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+
+/// An I2C bus wrapper that remembers the most recent error. Errors are still returned as normal,
+/// but a draw loop that ignores the results of individual calls can collect failures later with
+/// [take_last_error](Self::take_last_error). Usually created through
+/// [with_error_latch](crate::IS31FL3731::with_error_latch).
+pub struct LatchI2c<I2C: ErrorType> {
+    /// The wrapped i2c bus.
+    pub i2c: I2C,
+    last_error: Option<I2C::Error>,
+}
+
+impl<I2C: ErrorType> LatchI2c<I2C> {
+    /// Wrap `i2c` so that its errors are remembered.
+    pub fn new(i2c: I2C) -> Self {
+        Self {
+            i2c,
+            last_error: None,
+        }
+    }
+
+    /// Get the most recent error, if any happened since this was last called.
+    pub fn take_last_error(&mut self) -> Option<I2C::Error> {
+        self.last_error.take()
+    }
+
+    /// Get back the wrapped i2c bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C> LatchI2c<I2C>
+where
+    I2C: I2c,
+    I2C::Error: Clone,
+{
+    fn latch<T>(&mut self, result: Result<T, I2C::Error>) -> Result<T, I2C::Error> {
+        if let Err(error) = &result {
+            self.last_error = Some(error.clone());
+        }
+        result
+    }
+}
+
+impl<I2C: ErrorType> ErrorType for LatchI2c<I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C> I2c for LatchI2c<I2C>
+where
+    I2C: I2c,
+    I2C::Error: Clone,
+{
+    fn read(&mut self, address: SevenBitAddress, read: &mut [u8]) -> Result<(), Self::Error> {
+        let result = self.i2c.read(address, read);
+        self.latch(result)
+    }
+
+    fn write(&mut self, address: SevenBitAddress, write: &[u8]) -> Result<(), Self::Error> {
+        let result = self.i2c.write(address, write);
+        self.latch(result)
+    }
+
+    fn write_read(
+        &mut self,
+        address: SevenBitAddress,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c.write_read(address, write, read);
+        self.latch(result)
+    }
+
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        let result = self.i2c.transaction(address, operations);
+        self.latch(result)
+    }
+}
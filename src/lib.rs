@@ -1,11 +1,39 @@
 #![no_std]
 #![doc = include_str!("../README.md")]
 
+/// Effects played frame by frame
+pub mod animation;
+/// Frame buffers sized to a device
+pub mod buffer;
+/// Multiple devices combined into one display
+pub mod chain;
 /// Preconfigured devices
 pub mod devices;
+/// Temporal dithering for finer brightness steps
+pub mod dither;
+/// Tear-free double buffering
+pub mod double_buffer;
+/// Small fonts for drawing text
+#[cfg(feature = "font")]
+pub mod font;
+/// Error remembering I2C bus wrapper
+pub mod latch;
+/// Retrying I2C bus wrapper
+pub mod retry;
+/// Shutting the display down when the driver is dropped
+pub mod shutdown;
+/// Small images for the device wrappers
+pub mod sprite;
+
+#[cfg(test)]
+mod mock;
 
 use embedded_hal::delay::DelayNs;
-use embedded_hal::i2c::I2c;
+use embedded_hal::i2c::ErrorType;
+use embedded_hal::i2c::{I2c, Operation};
+use latch::LatchI2c;
+use retry::RetryI2c;
+use shutdown::ShutdownOnDrop;
 
 /// A struct to integrate with a new IS31FL3731 powered device.
 pub struct IS31FL3731<I2C> {
@@ -16,22 +44,256 @@ pub struct IS31FL3731<I2C> {
     pub address: u8,
     /// The current frame register in use.
     frame: u8,
+    /// The last known state of the LED enable registers, one bit per LED.
+    enable_state: [u8; 18],
+    /// The last known state of the blink control registers, one bit per LED.
+    blink_state: [u8; 18],
+    /// The register bank last selected on the chip, or `None` if it isn't known.
+    current_bank: Option<u8>,
+    /// The mode register value that starts the configured autoplay movie, or `None` until one is
+    /// configured.
+    autoplay_mode: Option<u8>,
+    /// The frame a paused autoplay movie was frozen on.
+    paused_frame: Option<u8>,
+    /// Whether brightness values are inverted before they are written.
+    inverted: bool,
+    /// The most brightness values sent in a single write.
+    max_chunk: usize,
+    /// The number of i2c transactions sent since the counter was last reset.
+    #[cfg(feature = "metrics")]
+    transactions_sent: u32,
 }
 
 impl<I2C> IS31FL3731<I2C> {
-    /// Creates and sets up a new instance of the IS31FL3731 driver.
+    /// Creates and sets up a new instance of the IS31FL3731 driver. The address must be the
+    /// 7-bit address, builds with debug assertions panic on an 8-bit address that includes the
+    /// read/write bit (such as `0xE8`). Use [new_checked](Self::new_checked) to get an error
+    /// instead.
     pub fn new(i2c: I2C, address: u8) -> Self {
+        debug_assert!(
+            address <= 0x7F,
+            "i2c address {address:#04x} isn't a 7-bit address"
+        );
         Self {
             i2c,
             address,
             frame: 0,
+            enable_state: [0; 18],
+            blink_state: [0; 18],
+            current_bank: None,
+            autoplay_mode: None,
+            paused_frame: None,
+            inverted: false,
+            max_chunk: LED_COUNT,
+            #[cfg(feature = "metrics")]
+            transactions_sent: 0,
         }
     }
 
     /// Change the slave address to a new 7-bit address. Should be configured before calling
     /// [setup](Self::setup) method.
     pub fn set_address(&mut self, address: u8) {
+        debug_assert!(
+            address <= 0x7F,
+            "i2c address {address:#04x} isn't a 7-bit address"
+        );
         self.address = address;
+        self.current_bank = None;
+    }
+
+    /// Like [new](Self::new) but returns an [InvalidAddress](Error::InvalidAddress) error if
+    /// `address` is above `0x7F`, which usually means the 8-bit address including the read/write
+    /// bit was passed instead of the 7-bit one.
+    pub fn new_checked(i2c: I2C, address: u8) -> Result<Self, Error<I2C::Error>>
+    where
+        I2C: ErrorType,
+    {
+        if address > 0x7F {
+            return Err(Error::InvalidAddress(address));
+        }
+        Ok(Self::new(i2c, address))
+    }
+
+    /// Forget which register bank is selected on the chip so the next write selects it again.
+    /// The driver skips selecting a bank that is already selected, which relies on nothing else
+    /// changing the selection. [setup](Self::setup_blocking) and [reset](Self::reset_blocking)
+    /// already do this, but call it if the chip may have been reset behind the driver's back.
+    pub fn invalidate_bank_cache(&mut self) {
+        self.current_bank = None;
+    }
+
+    /// The mode register value that resumes the autoplay movie, continuing from the paused frame
+    /// if there is one, or `None` if no movie has been configured.
+    fn resume_mode(&self) -> Option<u8> {
+        let mode = self.autoplay_mode?;
+        Some(match self.paused_frame {
+            Some(frame) => (mode & !addresses::MODE_START_FRAME_MASK) | frame,
+            None => mode,
+        })
+    }
+
+    /// Set the cached enable bits of the `len` LEDs starting at `start` and return the range of
+    /// enable register bytes that changed, or `None` if `len` is 0.
+    fn update_enable_region<E>(
+        &mut self,
+        start: u8,
+        len: u8,
+        enabled: bool,
+    ) -> Result<Option<(usize, usize)>, Error<E>> {
+        let end = start as usize + len as usize;
+        if end > LED_COUNT {
+            return Err(Error::InvalidLocation(start));
+        }
+        if len == 0 {
+            return Ok(None);
+        }
+        for led in start as usize..end {
+            if enabled {
+                self.enable_state[led / 8] |= 1 << (led % 8);
+            } else {
+                self.enable_state[led / 8] &= !(1 << (led % 8));
+            }
+        }
+        Ok(Some((start as usize / 8, (end - 1) / 8)))
+    }
+
+    /// Invert every brightness written from now on, so 0 is fully on and 255 is off, like a
+    /// photo negative. This applies to the pixel, fill and all_pixels style methods, and values
+    /// read back from the chip are inverted again so they match what was written. Apply
+    /// [gamma] before passing values in; the inversion happens after it. Nothing already on the
+    /// display changes until it is drawn again. Clearing and setup are not inverted and always
+    /// turn the LEDs off.
+    pub fn set_inverted(&mut self, on: bool) {
+        self.inverted = on;
+    }
+
+    /// Limit how many brightness values are sent in a single write, for HALs that can't handle
+    /// long i2c transfers. Longer writes such as [all_pixels](Self::all_pixels_blocking) are split
+    /// into several writes that continue where the previous one stopped. Each write also carries
+    /// the register address, so it is one byte longer than `max_chunk`. The default of 144 sends
+    /// every LED in one write, and a `max_chunk` of 0 is treated as 1.
+    pub fn set_max_chunk(&mut self, max_chunk: usize) {
+        self.max_chunk = max_chunk.max(1);
+    }
+
+    /// The number of i2c transactions the driver has sent since it was created or the counter was
+    /// last [reset](Self::reset_counter), including bank selects. Useful for measuring what an
+    /// animation loop costs on the bus.
+    #[cfg(feature = "metrics")]
+    pub fn counter(&self) -> u32 {
+        self.transactions_sent
+    }
+
+    /// Reset the [counter](Self::counter) to 0.
+    #[cfg(feature = "metrics")]
+    pub fn reset_counter(&mut self) {
+        self.transactions_sent = 0;
+    }
+
+    /// Count an i2c transaction if the `metrics` feature is enabled.
+    fn count_transaction(&mut self) {
+        #[cfg(feature = "metrics")]
+        {
+            self.transactions_sent = self.transactions_sent.wrapping_add(1);
+        }
+    }
+
+    /// `brightness` as it should be written to the chip.
+    fn apply_inversion(&self, brightness: u8) -> u8 {
+        if self.inverted {
+            255 - brightness
+        } else {
+            brightness
+        }
+    }
+
+    /// Set the cached blink bits of `leds` and return the range of blink register bytes that
+    /// changed, or `None` if `leds` is empty.
+    fn update_blink_state<E>(
+        &mut self,
+        leds: &[u8],
+        blink: bool,
+    ) -> Result<Option<(usize, usize)>, Error<E>> {
+        if let Some(led) = leds.iter().find(|led| **led as usize >= LED_COUNT) {
+            return Err(Error::InvalidLocation(*led));
+        }
+        let mut range: Option<(usize, usize)> = None;
+        for led in leds.iter().map(|led| *led as usize) {
+            if blink {
+                self.blink_state[led / 8] |= 1 << (led % 8);
+            } else {
+                self.blink_state[led / 8] &= !(1 << (led % 8));
+            }
+            range = match range {
+                Some((first, last)) => Some((first.min(led / 8), last.max(led / 8))),
+                None => Some((led / 8, led / 8)),
+            };
+        }
+        Ok(range)
+    }
+
+    /// Retry every failed i2c transfer up to `attempts` times, waiting `retry_ms` between each
+    /// attempt. If every attempt fails the error from the last one is returned. This is meant for
+    /// field hardware where an occasional glitch on the bus shouldn't stop an animation.
+    pub fn with_retries<D: DelayNs>(
+        self,
+        attempts: u8,
+        delay: D,
+        retry_ms: u16,
+    ) -> IS31FL3731<RetryI2c<I2C, D>> {
+        IS31FL3731 {
+            i2c: RetryI2c::new(self.i2c, delay, attempts, retry_ms),
+            address: self.address,
+            frame: self.frame,
+            enable_state: self.enable_state,
+            blink_state: self.blink_state,
+            current_bank: self.current_bank,
+            autoplay_mode: self.autoplay_mode,
+            paused_frame: self.paused_frame,
+            inverted: self.inverted,
+            max_chunk: self.max_chunk,
+            #[cfg(feature = "metrics")]
+            transactions_sent: self.transactions_sent,
+        }
+    }
+
+    /// Remember the most recent i2c error so it can be collected later with
+    /// [take_last_error](IS31FL3731::take_last_error). This is useful in tight animation loops
+    /// where checking the result of every call is cumbersome but errors should still surface.
+    pub fn with_error_latch(self) -> IS31FL3731<LatchI2c<I2C>>
+    where
+        I2C: ErrorType,
+    {
+        IS31FL3731 {
+            i2c: LatchI2c::new(self.i2c),
+            address: self.address,
+            frame: self.frame,
+            enable_state: self.enable_state,
+            blink_state: self.blink_state,
+            current_bank: self.current_bank,
+            autoplay_mode: self.autoplay_mode,
+            paused_frame: self.paused_frame,
+            inverted: self.inverted,
+            max_chunk: self.max_chunk,
+            #[cfg(feature = "metrics")]
+            transactions_sent: self.transactions_sent,
+        }
+    }
+
+    /// Put the display to sleep when the driver goes out of scope, so it doesn't stay lit after
+    /// a panic or teardown. Use [release](ShutdownOnDrop::release) to keep it running.
+    pub fn shutdown_on_drop(self) -> ShutdownOnDrop<I2C>
+    where
+        I2C: I2c,
+    {
+        ShutdownOnDrop::new(self)
+    }
+}
+
+impl<I2C: ErrorType> IS31FL3731<LatchI2c<I2C>> {
+    /// Get the most recent i2c error, if any happened since this was last called.
+    pub fn take_last_error(&mut self) -> Option<I2C::Error> {
+        self.i2c.take_last_error()
     }
 }
 
@@ -39,36 +301,132 @@ impl<I2C, I2cError> IS31FL3731<I2C>
 where
     I2C: I2c<Error = I2cError>,
 {
-    /// Fill the display with a single brightness. The brightness should range from 0 to 255. The reason that blink is an optional is
-    /// because you can either set blink to true, set blink to false, or not set blink at all. The
+    /// Try each of the standard [ADDRESSES] in order by selecting the configuration
+    /// bank, returning the first one that acknowledges. This is helpful if you don't know how the
+    /// AD pin of your board is strapped. The returned address can then be passed to
+    /// [new](Self::new).
+    pub fn probe_address_blocking(i2c: &mut I2C) -> Option<u8> {
+        ADDRESSES.into_iter().find(|&address| {
+            i2c.write(address, &[addresses::BANK_ADDRESS, addresses::CONFIG_BANK])
+                .is_ok()
+        })
+    }
+
+    /// Fill the display with a single brightness. The brightness should range from 0 to 255. The
+    /// blink setting turns blinking on or off for every LED in the frame, or with
+    /// [Leave](BlinkSetting::Leave) keeps the blink registers as they are. Those registers are
+    /// undefined until the frame is cleared or blink is set, so use `On` or `Off` on a fresh frame.
+    /// An `Option<bool>` is still accepted, `None` being `Leave`. The
     /// frame is the frame in which the fill should be applied to. Please consult the "General
     /// Description" section on the first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
-    /// for more information on frames.
+    /// for more information on frames. Filling a frame never changes which frame is displayed.
+    /// LEDs that are disabled stay off since the chip masks them, but they keep the new
+    /// brightness and show it if they are enabled later.
     pub fn fill_blocking(
         &mut self,
         brightness: u8,
-        blink: Option<bool>,
+        blink: impl Into<BlinkSetting>,
         frame: u8,
     ) -> Result<(), I2cError> {
-        self.bank_blocking(frame)?;
-        let mut payload = [brightness; 25];
-        for row in 0..6 {
-            payload[0] = addresses::COLOR_OFFSET + row * 24;
-            self.i2c.write(self.address, &payload)?;
+        let brightness = self.apply_inversion(brightness);
+        self.fill_raw_blocking(brightness, blink.into(), frame)
+    }
+
+    /// Prepare `frame` by setting every LED in it to `brightness`, without changing which frame
+    /// is displayed or the driver's current frame. The frame must be less than 8, otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub fn fill_into_blocking(&mut self, frame: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
         }
-        if blink.is_some() {
-            let data = if blink.unwrap() { 1 } else { 0 } * 0xFF;
-            for col in 0..18 {
-                self.write_register_blocking(frame, addresses::BLINK_OFFSET + col, data)?;
+        self.fill_blocking(brightness, BlinkSetting::Leave, frame)?;
+        Ok(())
+    }
+
+    /// Set every LED of all 8 frames to `brightness`, e.g. to start an autoplay movie from a
+    /// known state. Like [fill_into](Self::fill_into_blocking) neither the displayed frame nor
+    /// the driver's current frame are changed.
+    pub fn fill_all_frames_blocking(&mut self, brightness: u8) -> Result<(), I2cError> {
+        for frame in 0..8 {
+            self.fill_blocking(brightness, BlinkSetting::Leave, frame)?;
+        }
+        Ok(())
+    }
+
+    /// Set every LED in the current frame to `brightness` and then display that frame.
+    pub fn fill_current_and_show_blocking(
+        &mut self,
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        self.fill_blocking(brightness, BlinkSetting::Leave, self.frame)?;
+        self.display_frame_blocking(self.frame)
+    }
+
+    /// Fill the current frame with `brightness` on the LEDs that are enabled, setting the
+    /// disabled ones to 0 so they stay dark even if they are enabled later. This uses the
+    /// driver's cached enable state from [set_led_enabled](Self::set_led_enabled_blocking) and
+    /// friends.
+    pub fn fill_enabled_only_blocking(&mut self, brightness: u8) -> Result<(), Error<I2cError>> {
+        let frame = enabled_only(&self.enable_state, brightness);
+        self.all_pixels_blocking(&frame)
+    }
+
+    /// Like [fill](Self::fill_blocking) but also able to enable every LED of the frame first. The
+    /// enable registers are written before the brightness and blink so that the fill is visible
+    /// even on a chip that hasn't been [setup](Self::setup_blocking). When `enable_all` is false the
+    /// enable registers are left untouched.
+    pub fn fill_full_blocking(
+        &mut self,
+        brightness: u8,
+        blink: bool,
+        enable_all: bool,
+        frame: u8,
+    ) -> Result<(), I2cError> {
+        if enable_all {
+            self.bank_blocking(frame)?;
+            let payload = register_run::<19>(addresses::ENABLE_OFFSET, 0xFF);
+            self.count_transaction();
+            self.i2c.write(self.address, &payload)?;
+            if frame == self.frame {
+                self.enable_state = [0xFF; 18];
             }
         }
+        self.fill_blocking(brightness, Some(blink), frame)
+    }
+
+    /// Turn blinking on or off for every LED of `frame` without touching its brightness, so blink
+    /// can be toggled on an already drawn frame. The frame must be less than 8, otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub fn frame_blink_all_blocking(
+        &mut self,
+        frame: u8,
+        blink: bool,
+    ) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.bank_blocking(frame)?;
+        let data = if blink { 0xFF } else { 0 };
+        let payload = register_run::<19>(addresses::BLINK_OFFSET, data);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload)?;
+        if frame == self.frame {
+            self.blink_state = [data; 18];
+        }
         Ok(())
     }
 
-    /// Clear the display. This is a convenience method that calls [fill](Self::fill) with a
-    /// brightness of 0 for the current frame.
+    /// Clear the display by turning every LED of the current frame off. Unlike a
+    /// [fill](Self::fill_blocking) with a brightness of 0 this ignores
+    /// [inversion](Self::set_inverted), so the LEDs are dark either way.
     pub fn clear_blocking(&mut self) -> Result<(), I2cError> {
-        self.fill_blocking(0, None, self.frame)
+        self.fill_raw_blocking(0, BlinkSetting::Leave, self.frame)
+    }
+
+    /// Set every LED of the current frame to `brightness` as fast as possible. Only the 6
+    /// brightness row writes are sent, the blink registers are never touched.
+    pub fn clear_to_blocking(&mut self, brightness: u8) -> Result<(), I2cError> {
+        self.fill_blocking(brightness, BlinkSetting::Leave, self.frame)
     }
 
     /// Setup the display. Should be called before interacting with the device to ensure proper
@@ -81,18 +439,102 @@ where
     /// 3. Audio syncing will be turned off.
     /// 4. The chip will be told that it's being turned back on.
     pub fn setup_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2cError>> {
-        self.sleep_blocking(true)?;
-        delay.delay_ms(10);
-        self.mode_blocking(addresses::PICTURE_MODE)?;
-        self.frame_blocking(0)?;
-        for frame in 0..8 {
-            self.fill_blocking(0, Some(false), frame)?;
-            for col in 0..18 {
-                self.write_register_blocking(frame, addresses::ENABLE_OFFSET + col, 0xFF)?;
+        self.setup_frames_blocking(delay, 8)
+    }
+
+    /// Like [setup](Self::setup_blocking) but only clears and enables the first `frames` frames.
+    /// Boards that only ever draw to frame 0 can pass 1 here to cut the i2c traffic of setup by
+    /// roughly 8 times. If `frames` is more than 8 then an [InvalidFrame](Error::InvalidFrame)
+    /// error is returned.
+    pub fn setup_frames_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        frames: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if frames > 8 {
+            return Err(Error::InvalidFrame(frames));
+        }
+        self.setup_with_enables_blocking(delay, frames, [0xFF; 18])
+    }
+
+    /// Like [setup](Self::setup_blocking) but with `preserve_enables` set the LEDs are enabled
+    /// as they last were through this driver, e.g. with
+    /// [set_led_enabled](Self::set_led_enabled_blocking), instead of all being enabled. This lets
+    /// a sparse matrix be set up again after an error without turning its unpopulated LEDs back
+    /// on. A new driver hasn't enabled any LEDs yet, so preserving before the first setup leaves
+    /// every LED disabled.
+    pub fn setup_ex_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        preserve_enables: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let enables = if preserve_enables {
+            self.enable_state
+        } else {
+            [0xFF; 18]
+        };
+        self.setup_with_enables_blocking(delay, 8, enables)
+    }
+
+    /// Run [setup](Self::setup_blocking) and then fade `target` in on frame 0 over roughly `ms`
+    /// milliseconds instead of snapping it on. The chip is taken out of shutdown by the setup
+    /// before the fade starts so the whole ramp is visible.
+    pub fn setup_and_fade_in_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        target: &[u8; LED_COUNT],
+        ms: u16,
+    ) -> Result<(), Error<I2cError>> {
+        self.setup_blocking(delay)?;
+        for step in 1..=FADE_STEPS {
+            self.all_pixels_blocking(&scale_frame(target, step, FADE_STEPS))?;
+            delay.delay_ms((ms / FADE_STEPS) as u32);
+        }
+        Ok(())
+    }
+
+    /// Fade the current frame out to black over roughly `ms` milliseconds, the counterpart to
+    /// [setup_and_fade_in](Self::setup_and_fade_in_blocking). The frame is read back from the chip
+    /// once before the fade starts, so no copy of it needs to be kept. If `sleep` is true the chip
+    /// is put into shutdown once the frame is dark.
+    pub fn fade_out_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        ms: u16,
+        sleep: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let start = self.read_all_pixels_blocking()?;
+        for step in (0..FADE_STEPS).rev() {
+            self.all_pixels_blocking(&scale_frame(&start, step, FADE_STEPS))?;
+            delay.delay_ms((ms / FADE_STEPS) as u32);
+        }
+        if sleep {
+            self.sleep_blocking(true)?;
+        }
+        Ok(())
+    }
+
+    /// Pulse the current frame by dimming it to black and back along a smooth, roughly sinusoidal
+    /// curve, each cycle taking about `period_ms` milliseconds. Unlike the chip's breath feature
+    /// this keeps the pattern of the frame, every LED is scaled relative to its own brightness.
+    /// The frame is read back from the chip once before the first cycle and is left as it was
+    /// when the last cycle ends. The pulse repeats `cycles` times, or forever if `cycles` is 0.
+    pub fn breathe_current_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        period_ms: u16,
+        cycles: u8,
+    ) -> Result<(), Error<I2cError>> {
+        let start = self.read_all_pixels_blocking()?;
+        let step_ms = period_ms as u32 / (2 * FADE_STEPS as u32);
+        let mut breathed = 0;
+        while cycles == 0 || breathed < cycles {
+            for step in 1..=2 * FADE_STEPS {
+                self.all_pixels_blocking(&scale_frame(&start, breath_level(step), 255))?;
+                delay.delay_ms(step_ms);
             }
+            breathed = breathed.saturating_add(1);
         }
-        self.audio_sync_blocking(false)?;
-        self.sleep_blocking(false)?;
         Ok(())
     }
 
@@ -103,198 +545,1446 @@ where
         if led >= LED_COUNT as u8 {
             return Err(Error::InvalidLocation(led));
         }
+        let brightness = self.apply_inversion(brightness);
         self.write_register_blocking(self.frame, addresses::COLOR_OFFSET + led, brightness)?;
         Ok(())
     }
 
-    /// Individially assign and updated brightness values for all 144 LEDs at once.
-    pub fn all_pixels_blocking(&mut self, buf: &[u8; LED_COUNT]) -> Result<(), Error<I2cError>> {
-        self.bank_blocking(self.frame)?;
-        let mut payload = [0; 145];
-        payload[0] = addresses::COLOR_OFFSET;
-        payload[1..].clone_from_slice(buf);
-        self.i2c.write(self.address, &payload)?;
+    /// Like [pixel](Self::pixel_blocking) but writes to `frame` instead of the current frame,
+    /// without changing the driver's current frame or which frame is displayed. This is the per
+    /// LED counterpart to [fill](Self::fill_blocking) with a frame and is handy for drawing a
+    /// hidden frame. The frame must be less than 8, otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub fn pixel_in_frame_blocking(
+        &mut self,
+        frame: u8,
+        led: u8,
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
+        }
+        let brightness = self.apply_inversion(brightness);
+        self.write_register_blocking(frame, addresses::COLOR_OFFSET + led, brightness)?;
         Ok(())
     }
 
-    /// Set frame ranging from 0 to 8. Please consult the "General Description" section on the
-    /// first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
-    /// for more information on frames.
-    pub fn frame_blocking(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
-        if frame > 8 {
-            return Err(Error::InvalidLocation(frame));
+    /// Read the brightness of a specific LED in the current frame, replace it with the result of
+    /// `f` and write it back. This is handy for simple effects like fading an LED towards zero
+    /// without keeping a buffer, but costs a read and a write on the bus for every call. If the
+    /// LED is out of range then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub fn update_pixel_blocking(
+        &mut self,
+        led: u8,
+        f: impl Fn(u8) -> u8,
+    ) -> Result<(), Error<I2cError>> {
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
         }
-        self.frame = frame;
-        self.write_register_blocking(addresses::CONFIG_BANK, addresses::FRAME, frame)?;
-        Ok(())
+        let brightness = self.read_register_blocking(self.frame, addresses::COLOR_OFFSET + led)?;
+        self.pixel_blocking(led, f(self.apply_inversion(brightness)))
     }
 
-    /// Send a reset message to the slave device. Delay is something that your device's HAL should
-    /// provide which allows for the process to sleep for a certain amount of time (in this case 10
-    /// MS to perform a reset).
-    pub fn reset_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), I2cError> {
-        self.sleep_blocking(true)?;
-        delay.delay_ms(10);
-        self.sleep_blocking(false)?;
+    /// Set the brightness of `buf.len()` consecutive LEDs starting at `first` in a single write.
+    /// If the run goes past the last LED an [InvalidLocation](Error::InvalidLocation) error is
+    /// returned.
+    pub fn pixels_blocking(&mut self, first: u8, buf: &[u8]) -> Result<(), Error<I2cError>> {
+        if first as usize + buf.len() > LED_COUNT {
+            return Err(Error::InvalidLocation(first));
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        self.write_pixels_blocking(self.frame, first, buf)?;
         Ok(())
     }
 
-    /// Set the device mode. Please consult page 17 and 18 of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
-    /// to learn mode about the different modes.
-    pub fn mode_blocking(&mut self, mode: u8) -> Result<(), I2cError> {
-        self.write_register_blocking(addresses::CONFIG_BANK, addresses::MODE_REGISTER, mode)?;
+    /// Write a sparse set of `(led, brightness)` changes to the current frame. Runs of changes to
+    /// consecutive LEDs are coalesced into a single write each, so sort the changes by LED to get
+    /// the fewest writes. Every LED is checked before anything is written, returning an
+    /// [InvalidLocation](Error::InvalidLocation) error if one is out of range.
+    pub fn apply_delta_blocking(&mut self, changes: &[(u8, u8)]) -> Result<(), Error<I2cError>> {
+        check_delta(changes)?;
+        let mut run = [0; LED_COUNT];
+        let mut rest = changes;
+        while !rest.is_empty() {
+            let (first, len) = delta_run(rest, &mut run);
+            self.write_pixels_blocking(self.frame, first, &run[..len])?;
+            rest = &rest[len..];
+        }
         Ok(())
     }
 
-    /// Set the slave device to sync audio
-    pub fn audio_sync_blocking(&mut self, yes: bool) -> Result<(), I2cError> {
-        self.write_register_blocking(
-            addresses::CONFIG_BANK,
-            addresses::AUDIOSYNC,
-            if yes { 1 } else { 0 },
-        )?;
+    /// Individially assign and updated brightness values for all 144 LEDs at once.
+    pub fn all_pixels_blocking(&mut self, buf: &[u8; LED_COUNT]) -> Result<(), Error<I2cError>> {
+        self.write_pixels_blocking(self.frame, 0, buf)?;
         Ok(())
     }
 
-    /// Set the device to sleep
-    pub fn sleep_blocking(&mut self, yes: bool) -> Result<(), I2cError> {
-        self.write_register_blocking(
-            addresses::CONFIG_BANK,
-            addresses::SHUTDOWN,
-            if yes { 0 } else { 1 },
-        )?;
+    /// Like [all_pixels](Self::all_pixels_blocking) but for a buffer whose size is only known at
+    /// runtime. If `buf` isn't exactly 144 bytes long nothing is written and an
+    /// [InvalidLocation](Error::InvalidLocation) error with the length (capped at 255) is
+    /// returned.
+    pub fn all_pixels_slice_blocking(&mut self, buf: &[u8]) -> Result<(), Error<I2cError>> {
+        if buf.len() != LED_COUNT {
+            return Err(Error::InvalidLocation(buf.len().min(255) as u8));
+        }
+        self.write_pixels_blocking(self.frame, 0, buf)?;
         Ok(())
     }
 
-    fn write_register_blocking(
+    /// Like [all_pixels](Self::all_pixels_blocking) but the brightness values come from an
+    /// iterator, e.g. `(0..144).map(|i| (i * 7) as u8)`, so no buffer needs to be built up front.
+    /// Only the first 144 values are used, the rest are never pulled from the iterator. If it
+    /// ends early the remaining LEDs are set to 0.
+    pub fn all_pixels_from_blocking(
         &mut self,
-        bank: u8,
-        register: u8,
-        value: u8,
-    ) -> Result<(), I2cError> {
-        self.bank_blocking(bank)?;
-        self.i2c.write(self.address, &[register, value])?;
-        Ok(())
+        values: impl IntoIterator<Item = u8>,
+    ) -> Result<(), Error<I2cError>> {
+        let mut buf = [0; LED_COUNT];
+        for (led, value) in buf.iter_mut().zip(values) {
+            *led = value;
+        }
+        self.all_pixels_blocking(&buf)
     }
 
-    fn bank_blocking(&mut self, bank: u8) -> Result<(), I2cError> {
-        self.i2c
-            .write(self.address, &[addresses::BANK_ADDRESS, bank])?;
-        Ok(())
+    /// Decode a run-length encoded frame and write it to the current frame in a single write.
+    /// `rle` is a list of `(count, value)` byte pairs, each setting the next `count` LEDs to
+    /// `value`, and the runs must add up to exactly 144 LEDs. This lets animations be stored in
+    /// flash in far less space than 144 bytes a frame. If the data is malformed nothing is
+    /// written and an [InvalidLocation](Error::InvalidLocation) error with the number of LEDs
+    /// decoded (capped at 255) is returned.
+    pub fn load_rle_frame_blocking(&mut self, rle: &[u8]) -> Result<(), Error<I2cError>> {
+        self.all_pixels_blocking(&decode_rle(rle)?)
     }
-}
 
-#[cfg(feature = "async")]
-impl<I2C, I2cError> IS31FL3731<I2C>
-where
-    I2C: embedded_hal_async::i2c::I2c<Error = I2cError>,
-{
-    /// Fill the display with a single brightness. The brightness should range from 0 to 255. The reason that blink is an optional is
-    /// because you can either set blink to true, set blink to false, or not set blink at all. The
-    /// frame is the frame in which the fill should be applied to. Please consult the "General
-    /// Description" section on the first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
-    /// for more information on frames.
-    pub async fn fill(
+    /// Play a precomputed animation on the current frame by writing each of `frames` in turn with
+    /// [all_pixels](Self::all_pixels_blocking), waiting `1000 / fps` milliseconds between them. The
+    /// sequence is repeated `loops` times, or forever if `loops` is 0. An `fps` of 0 is treated
+    /// as 1.
+    pub fn play_frames_blocking(
         &mut self,
-        brightness: u8,
-        blink: Option<bool>,
-        frame: u8,
-    ) -> Result<(), I2cError> {
-        self.bank(frame).await?;
-        let mut payload = [brightness; 25];
-        for row in 0..6 {
-            payload[0] = addresses::COLOR_OFFSET + row * 24;
-            self.i2c.write(self.address, &payload).await?;
-        }
-        if blink.is_some() {
-            let data = if blink.unwrap() { 1 } else { 0 } * 0xFF;
-            for col in 0..18 {
-                self.write_register(frame, addresses::BLINK_OFFSET + col, data)
-                    .await?;
+        frames: &[[u8; LED_COUNT]],
+        fps: u8,
+        loops: u8,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        let frame_ms = 1000 / fps.max(1) as u32;
+        let mut played = 0;
+        while loops == 0 || played < loops {
+            for frame in frames {
+                self.all_pixels_blocking(frame)?;
+                delay.delay_ms(frame_ms);
             }
+            played = played.saturating_add(1);
         }
         Ok(())
     }
 
-    /// Clear the display. This is a convenience method that calls [fill](Self::fill) with a
-    /// brightness of 0 for the current frame.
-    pub async fn clear(&mut self) -> Result<(), I2cError> {
-        self.fill(0, None, self.frame).await?;
+    /// Like [play_frames](Self::play_frames_blocking) but the frames come from an iterator, so
+    /// they can be generated lazily instead of being stored up front. Playback ends when the
+    /// iterator does.
+    pub fn play_frames_iter_blocking(
+        &mut self,
+        frames: impl IntoIterator<Item = [u8; LED_COUNT]>,
+        fps: u8,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        let frame_ms = 1000 / fps.max(1) as u32;
+        for frame in frames {
+            self.all_pixels_blocking(&frame)?;
+            delay.delay_ms(frame_ms);
+        }
+        Ok(())
     }
 
+    /// Read back the brightness values of all 144 LEDs in the current frame. This is the read
+    /// counterpart to [all_pixels](Self::all_pixels_blocking) and is done as a single transfer.
+    pub fn read_all_pixels_blocking(&mut self) -> Result<[u8; LED_COUNT], I2cError> {
+        self.read_pixels_blocking(self.frame)
+    }
 
-    /// Setup the display. Should be called before interacting with the device to ensure proper
-    /// functionality. Delay is something that your device's HAL should provide which allows for
-    /// the process to sleep for a certain amount of time (in this case 10 MS to perform a reset).
-    ///
-    /// When you run this function the following steps will occur:
-    /// 1. The chip will be told that it's being "reset".
-    /// 2. All frames will be cleared.
-    /// 3. Audio syncing will be turned off.
-    /// 4. The chip will be told that it's being turned back on.
-    pub async fn setup(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<I2cError>> {
-        self.sleep(true).await?;
-        delay.delay_ms(10);
-        self.mode(addresses::PICTURE_MODE).await?;
-        self.frame(0).await?;
-        for frame in 0..8 {
-            self.fill(0, Some(false), frame).await?;
-            for col in 0..18 {
-                self.write_register(frame, addresses::ENABLE_OFFSET + col, 0xFF)
-                    .await?;
-            }
+    /// Scale the brightness of every LED in the current frame by `numerator / denominator`,
+    /// saturating at 255. Useful for dimming whatever is currently displayed (e.g. a fade-out)
+    /// without keeping a copy of it. Note that this reads all 144 PWM registers back from the chip
+    /// before writing them again, so it costs roughly twice the bus traffic of
+    /// [all_pixels](Self::all_pixels_blocking). A `denominator` of 0 returns an
+    /// [InvalidScale](Error::InvalidScale) error before anything is read.
+    pub fn scale_current_blocking(
+        &mut self,
+        numerator: u8,
+        denominator: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if denominator == 0 {
+            return Err(Error::InvalidScale(denominator));
         }
-        self.audio_sync(false).await?;
-        self.sleep(false).await?;
-        Ok(())
+        let buf = self.read_all_pixels_blocking()?;
+        self.all_pixels_blocking(&scale_frame(&buf, numerator as u16, denominator as u16))
     }
 
-    /// Set the brightness for a specific LED. Just like the [fill method](Self::fill) the
-    /// brightness should range from 0 to 255. If the LED is out of range then the function will
-    /// return an error of [InvalidLocation](Error::InvalidLocation).
-    pub async fn pixel(&mut self, led: u8, brightness: u8) -> Result<(), Error<I2cError>> {
-        if led >= LED_COUNT as u8 {
-            return Err(Error::InvalidLocation(led));
+    /// Write brightness values for all 144 LEDs into `frame` without changing which frame is
+    /// displayed or the driver's current frame. Selecting a frame's bank only decides which frame
+    /// the data is written to, the displayed picture is controlled by [frame](Self::frame_blocking).
+    /// This makes it possible to draw into a hidden frame and switch to it once it's complete.
+    /// The frame must be less than 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is
+    /// returned.
+    pub fn render_into_blocking(
+        &mut self,
+        frame: u8,
+        buf: &[u8; LED_COUNT],
+    ) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
         }
-        self.write_register(self.frame, addresses::COLOR_OFFSET + led, brightness)
-            .await?;
+        self.write_pixels_blocking(frame, 0, buf)?;
         Ok(())
     }
 
-    /// Individially assign and updated brightness values for all 144 LEDs at once.
-    pub async fn all_pixels(&mut self, buf: &[u8; LED_COUNT]) -> Result<(), Error<I2cError>> {
-        self.bank(self.frame).await?;
-        let mut payload = [0; 145];
-        payload[0] = addresses::COLOR_OFFSET;
-        payload[1..].clone_from_slice(buf);
-        self.i2c.write(self.address, &payload).await?;
+    /// Write all 8 frames at once, `frames[n]` going to frame `n`, which is the usual way to
+    /// load an [autoplay](Self::autoplay_blocking) movie. Each frame is sent in a single write
+    /// and, like [render_into](Self::render_into_blocking), neither the displayed frame nor the
+    /// driver's current frame are changed.
+    pub fn write_all_frames_blocking(
+        &mut self,
+        frames: &[[u8; LED_COUNT]; 8],
+    ) -> Result<(), I2cError> {
+        for (frame, buf) in (0..).zip(frames) {
+            self.write_pixels_blocking(frame, 0, buf)?;
+        }
         Ok(())
     }
 
-    /// Set frame ranging from 0 to 8. Please consult the "General Description" section on the
-    /// first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
-    /// for more information on frames.
-    pub async fn frame(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
-        if frame > 8 {
-            return Err(Error::InvalidLocation(frame));
+    /// Copy the brightness values of every LED in frame `src` into frame `dst`. Both frames must
+    /// be less than 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is returned. Neither
+    /// the displayed frame nor the current frame are changed, which makes this useful for
+    /// preparing a hidden frame before switching to it.
+    pub fn copy_frame_blocking(&mut self, src: u8, dst: u8) -> Result<(), Error<I2cError>> {
+        if src >= 8 {
+            return Err(Error::InvalidFrame(src));
         }
-        self.frame = frame;
-        self.write_register(addresses::CONFIG_BANK, addresses::FRAME, frame)
-            .await?;
+        if dst >= 8 {
+            return Err(Error::InvalidFrame(dst));
+        }
+        let buf = self.read_pixels_blocking(src)?;
+        self.write_pixels_blocking(dst, 0, &buf)?;
         Ok(())
     }
 
-    /// Send a reset message to the slave device. Delay is something that your device's HAL should
-    /// provide which allows for the process to sleep for a certain amount of time (in this case 10
-    /// MS to perform a reset).
-    pub async fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), I2cError> {
-        self.sleep(true).await?;
-        delay.delay_ms(10);
-        self.sleep(false).await?;
-        Ok(())
+    /// Enable or disable a single LED in the current frame. A disabled LED stays off regardless of
+    /// its brightness. If the LED is out of range then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub fn set_led_enabled_blocking(
+        &mut self,
+        led: u8,
+        enabled: bool,
+    ) -> Result<(), Error<I2cError>> {
+        self.enable_region_blocking(led, 1, enabled)
     }
 
-    /// Set the device mode. Please consult page 17 and 18 of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
+    /// Enable or disable `len` consecutive LEDs starting at `start` in the current frame with a
+    /// single write. This is handy for matrices where only part of the 144 LEDs are populated. If
+    /// the region doesn't fit within the 144 LEDs then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub fn enable_region_blocking(
+        &mut self,
+        start: u8,
+        len: u8,
+        enabled: bool,
+    ) -> Result<(), Error<I2cError>> {
+        if let Some((first, last)) = self.update_enable_region(start, len, enabled)? {
+            self.write_enable_state_blocking(first, last)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the enable state of every LED in the current frame at once. Each byte of `mask`
+    /// covers 8 LEDs with bit 0 being the lowest LED index.
+    pub fn set_enable_mask_blocking(&mut self, mask: &[u8; 18]) -> Result<(), I2cError> {
+        self.enable_state = *mask;
+        self.write_enable_state_blocking(0, 17)
+    }
+
+    /// Enable or disable every LED in the current frame with a single write. Disabling blanks
+    /// the display while keeping the brightness of every LED, so enabling them again brings the
+    /// picture straight back. Unlike [sleep](Self::sleep_blocking) the chip stays running.
+    pub fn enable_all_blocking(&mut self, enabled: bool) -> Result<(), I2cError> {
+        self.set_enable_mask_blocking(&[if enabled { 0xFF } else { 0 }; 18])
+    }
+
+    /// Turn blinking on or off for the listed LEDs of the current frame, leaving the blink of
+    /// every other LED as it was. The driver keeps a copy of the blink registers so only the
+    /// bytes covering the listed LEDs are written, in a single write. Blinking only shows once it
+    /// is enabled in the chip's display option register. If any LED is out of range then nothing
+    /// is written and the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub fn set_blink_leds_blocking(
+        &mut self,
+        leds: &[u8],
+        blink: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let Some((first, last)) = self.update_blink_state(leds, blink)? else {
+            return Ok(());
+        };
+        self.bank_blocking(self.frame)?;
+        let (payload, len) = state_payload(addresses::BLINK_OFFSET, &self.blink_state, first, last);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload[..len])?;
+        Ok(())
+    }
+
+    /// Set frame ranging from 0 to 8. Please consult the "General Description" section on the
+    /// first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
+    /// for more information on frames.
+    pub fn frame_blocking(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
+        if frame > 8 {
+            return Err(Error::InvalidLocation(frame));
+        }
+        self.frame = frame;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::FRAME, frame)?;
+        Ok(())
+    }
+
+    /// Read the frame register back from the chip and update the driver's cached frame to match.
+    /// Useful after a watchdog or brownout has reset the chip without the driver noticing, which
+    /// would otherwise leave pixel writes going to the wrong frame.
+    pub fn resync_frame_blocking(&mut self) -> Result<(), I2cError> {
+        self.current_bank = None;
+        let frame = self.read_register_blocking(addresses::CONFIG_BANK, addresses::FRAME)?;
+        self.frame = frame & addresses::FRAME_MASK;
+        Ok(())
+    }
+
+    /// Set the 24 LEDs of one of the chip's 6 hardware rows in the current frame to `brightness`,
+    /// using raw LED indices rather than any device mapping. This is meant for checking the
+    /// wiring of a freshly assembled board one row at a time. If the row is 6 or more an
+    /// [InvalidLocation](Error::InvalidLocation) error is returned.
+    pub fn light_row_blocking(
+        &mut self,
+        chip_row: u8,
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if chip_row >= 6 {
+            return Err(Error::InvalidLocation(chip_row));
+        }
+        self.pixels_blocking(chip_row * 24, &[brightness; 24])
+    }
+
+    /// Run a simple test pattern on the current frame to verify wiring after assembling a board.
+    /// Each LED is lit on its own in index order, then the whole frame is filled, then cleared.
+    /// `step_ms` is how long each step is shown for.
+    pub fn self_test_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        step_ms: u32,
+    ) -> Result<(), Error<I2cError>> {
+        for led in 0..LED_COUNT as u8 {
+            self.pixel_blocking(led, 0xFF)?;
+            delay.delay_ms(step_ms);
+            self.pixel_blocking(led, 0)?;
+        }
+        self.fill_blocking(0xFF, BlinkSetting::Leave, self.frame)?;
+        delay.delay_ms(step_ms);
+        self.clear_blocking()?;
+        Ok(())
+    }
+
+    /// Show `frame` on the display without changing the driver's current frame, so drawing
+    /// methods keep writing to the frame they were writing to before. The frame must be less than
+    /// 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub fn display_frame_blocking(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::FRAME, frame)?;
+        Ok(())
+    }
+
+    /// Send a reset message to the slave device. Delay is something that your device's HAL should
+    /// provide which allows for the process to sleep for a certain amount of time (in this case 10
+    /// MS to perform a reset). The display stays dark afterwards until [setup](Self::setup_blocking)
+    /// is called again, or use [reset_and_setup](Self::reset_and_setup_blocking) to do both.
+    pub fn reset_blocking(&mut self, delay: &mut impl DelayNs) -> Result<(), I2cError> {
+        self.reset_with_delay_blocking(delay, RESET_MS)
+    }
+
+    /// Like [reset](Self::reset_blocking) but holds the chip in shutdown for `reset_ms`
+    /// milliseconds instead of [RESET_MS]. Some parts need around 20 ms to settle, while a
+    /// shorter time can speed up boot on parts that don't.
+    pub fn reset_with_delay_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        reset_ms: u16,
+    ) -> Result<(), I2cError> {
+        self.current_bank = None;
+        self.sleep_blocking(true)?;
+        delay.delay_ms(reset_ms as u32);
+        self.sleep_blocking(false)?;
+        Ok(())
+    }
+
+    /// Reset the device and run the full [setup](Self::setup_blocking), leaving it ready to draw.
+    pub fn reset_and_setup_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        self.reset_blocking(delay)?;
+        self.setup_blocking(delay)
+    }
+
+    /// Set the device mode. Please consult page 17 and 18 of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
+    /// to learn mode about the different modes.
+    pub fn mode_blocking(&mut self, mode: u8) -> Result<(), I2cError> {
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::MODE_REGISTER, mode)?;
+        Ok(())
+    }
+
+    /// Play frames as a movie. The chip shows `frames` frames (1 to 8) beginning with `start`,
+    /// each for `delay_ms` milliseconds rounded down to the chip's 11 ms steps (from 11 up to
+    /// 704), and plays the movie `loops` times (up to 7) or forever if `loops` is 0. An
+    /// [InvalidFrame](Error::InvalidFrame) error is returned if `start` or `frames` is out of
+    /// range.
+    pub fn autoplay_blocking(
+        &mut self,
+        start: u8,
+        frames: u8,
+        loops: u8,
+        delay_ms: u16,
+    ) -> Result<(), Error<I2cError>> {
+        check_movie(start, frames)?;
+        let (control1, control2) = autoplay_control(frames, loops, delay_ms);
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY1, control1)?;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY2, control2)?;
+        let mode = addresses::AUTOPLAY_MODE | start;
+        self.autoplay_mode = Some(mode);
+        self.paused_frame = None;
+        self.mode_blocking(mode)?;
+        Ok(())
+    }
+
+    /// Like [autoplay](Self::autoplay_blocking) but with each frame fading in and out using the
+    /// breath feature, for a cross fading slideshow. `fade_in` and `fade_out` are the chip's
+    /// timing codes from 0 to 7, giving a fade of 26 ms times 2 to the power of the code. A code
+    /// above 7 returns an [InvalidTiming](Error::InvalidTiming) error. Everything is checked before
+    /// anything is written.
+    pub fn autoplay_with_breath_blocking(
+        &mut self,
+        start: u8,
+        frames: u8,
+        loops: u8,
+        delay_ms: u16,
+        fade_in: u8,
+        fade_out: u8,
+    ) -> Result<(), Error<I2cError>> {
+        check_movie(start, frames)?;
+        let (breath1, breath2) = breath_control(fade_in, fade_out)?;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::BREATH1, breath1)?;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::BREATH2, breath2)?;
+        self.autoplay_blocking(start, frames, loops, delay_ms)
+    }
+
+    /// Freeze an autoplay movie on the frame it is currently showing by switching to picture
+    /// mode. The frame becomes the current frame of the driver, and the autoplay settings stay in
+    /// the chip so [autoplay_resume](Self::autoplay_resume_blocking) can continue the movie from
+    /// it without reprogramming them.
+    pub fn autoplay_pause_blocking(&mut self) -> Result<(), I2cError> {
+        let state = self.read_register_blocking(addresses::CONFIG_BANK, addresses::FRAME_STATE)?;
+        let frame = state & addresses::FRAME_STATE_FRAME_MASK;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::FRAME, frame)?;
+        self.mode_blocking(addresses::PICTURE_MODE)?;
+        self.frame = frame;
+        self.paused_frame = Some(frame);
+        Ok(())
+    }
+
+    /// Switch back to autoplay mode after [autoplay_pause](Self::autoplay_pause_blocking) using
+    /// the settings of the last [autoplay](Self::autoplay_blocking) call. Only the mode register
+    /// is written. A paused movie continues from the frame it was paused on, otherwise it starts
+    /// again from its first frame. Nothing is written if no movie has been set up with
+    /// [autoplay](Self::autoplay_blocking).
+    pub fn autoplay_resume_blocking(&mut self) -> Result<(), I2cError> {
+        if let Some(mode) = self.resume_mode() {
+            self.mode_blocking(mode)?;
+            self.paused_frame = None;
+        }
+        Ok(())
+    }
+
+    /// Switch to audio play mode, where the chip picks which of the frames from `start_frame` to
+    /// `end_frame` to show from the amplitude of the audio input. An
+    /// [InvalidFrame](Error::InvalidFrame) error is returned unless
+    /// `start_frame <= end_frame < 8`. The frame window shares a register with the autoplay
+    /// settings, so call [autoplay](Self::autoplay_blocking) again before going back to autoplay.
+    pub fn audio_play_setup_blocking(
+        &mut self,
+        start_frame: u8,
+        end_frame: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if end_frame >= 8 {
+            return Err(Error::InvalidFrame(end_frame));
+        }
+        if start_frame > end_frame {
+            return Err(Error::InvalidFrame(start_frame));
+        }
+        let (control1, _) = autoplay_control(end_frame - start_frame + 1, 0, 0);
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY1, control1)?;
+        self.mode_blocking(addresses::AUDIOPLAY_MODE | start_frame)?;
+        Ok(())
+    }
+
+    /// Switch the chip to `mode`, writing any settings the mode needs before the mode register
+    /// so the display never shows a half configured state.
+    pub fn set_display_mode_blocking(&mut self, mode: DisplayMode) -> Result<(), Error<I2cError>> {
+        match mode {
+            DisplayMode::Picture { frame } => {
+                self.display_frame_blocking(frame)?;
+                self.mode_blocking(addresses::PICTURE_MODE)?;
+            }
+            DisplayMode::Autoplay {
+                start,
+                frames,
+                loops,
+                delay_ms,
+            } => self.autoplay_blocking(start, frames, loops, delay_ms)?,
+            DisplayMode::AudioPlay => self.mode_blocking(addresses::AUDIOPLAY_MODE)?,
+        }
+        Ok(())
+    }
+
+    /// Read the frame register and assert that it matches the driver's cached frame, catching
+    /// desyncs (e.g. after a [raw_write](Self::raw_write_blocking)) early. This only does anything
+    /// in builds with debug assertions enabled and is a no-op in release builds.
+    pub fn verify_frame_blocking(&mut self) -> Result<(), I2cError> {
+        #[cfg(debug_assertions)]
+        {
+            let frame = self.read_register_blocking(addresses::CONFIG_BANK, addresses::FRAME)?;
+            debug_assert_eq!(
+                frame & addresses::FRAME_MASK,
+                self.frame,
+                "frame register doesn't match the cached frame"
+            );
+        }
+        Ok(())
+    }
+
+    /// Check whether an autoplay movie has finished all of its loops since this was last called.
+    /// The interrupt bit in the frame state register is cleared by the chip when it's read, so
+    /// polling this between loops can be used to refill frames for long scripted animations.
+    pub fn autoplay_loop_finished_blocking(&mut self) -> Result<bool, I2cError> {
+        let state = self.read_register_blocking(addresses::CONFIG_BANK, addresses::FRAME_STATE)?;
+        Ok(state & addresses::FRAME_STATE_INT != 0)
+    }
+
+    /// Read back the shutdown, mode and frame state of the chip, which helps find out why a
+    /// display stays dark. Reading the frame state clears the movie finished flag on the chip,
+    /// like [autoplay_loop_finished](Self::autoplay_loop_finished_blocking) does.
+    pub fn status_blocking(&mut self) -> Result<ChipStatus, I2cError> {
+        let shutdown = self.read_register_blocking(addresses::CONFIG_BANK, addresses::SHUTDOWN)?;
+        let mode = self.read_register_blocking(addresses::CONFIG_BANK, addresses::MODE_REGISTER)?;
+        let state = self.read_register_blocking(addresses::CONFIG_BANK, addresses::FRAME_STATE)?;
+        Ok(ChipStatus {
+            shutdown: shutdown & addresses::SHUTDOWN_NORMAL == 0,
+            mode: mode & addresses::MODE_MASK,
+            displayed_frame: state & addresses::FRAME_STATE_FRAME_MASK,
+            movie_finished: state & addresses::FRAME_STATE_INT != 0,
+        })
+    }
+
+    /// Check that the chip at the address behaves like an IS31FL3731 by writing two test
+    /// patterns to the autoplay delay register and reading each one back, restoring the original
+    /// value afterwards. That register only matters while a movie is playing, whereas writing the
+    /// frame register would switch the displayed frame, so the display is left alone. If a
+    /// pattern doesn't come back an [UnexpectedReadback](Error::UnexpectedReadback) error with
+    /// the value read is returned, which usually means a different chip such as an IS31FL3733
+    /// with another register map. The original value is still written back in that case.
+    pub fn sanity_check_blocking(&mut self) -> Result<(), Error<I2cError>> {
+        let original = self.read_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY2)?;
+        for pattern in [0x2A, 0x15] {
+            self.write_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY2, pattern)?;
+            let value =
+                self.read_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY2)?;
+            if value != pattern {
+                // The readback error is the one worth reporting, so a failed restore is ignored.
+                let _ = self.write_register_blocking(
+                    addresses::CONFIG_BANK,
+                    addresses::AUTOPLAY2,
+                    original,
+                );
+                return Err(Error::UnexpectedReadback(value));
+            }
+        }
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::AUTOPLAY2, original)?;
+        Ok(())
+    }
+
+    /// Write `value` to any `register` of any `bank`, taking care of selecting the bank first.
+    /// This is an escape hatch for registers that don't have a dedicated method. Note that it
+    /// bypasses the state cached by the driver (such as the current frame or the LED enable
+    /// state), so writing to registers the driver also manages can leave that state stale.
+    pub fn raw_write_blocking(
+        &mut self,
+        bank: u8,
+        register: u8,
+        value: u8,
+    ) -> Result<(), I2cError> {
+        self.write_register_blocking(bank, register, value)
+    }
+
+    /// Write `data` to consecutive registers of `bank` starting at `start_register`. The chip
+    /// moves on to the next register after every byte it receives, which is how
+    /// [all_pixels](Self::all_pixels_blocking) sends all 144 brightness values at once, and this
+    /// makes the same bulk write available for any region such as the enable or blink registers.
+    /// Like [raw_write](Self::raw_write_blocking) it bypasses the state cached by the driver and
+    /// brightness values aren't inverted. The bank must be a frame below 8 or the
+    /// [configuration bank](addresses::CONFIG_BANK), otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned. If the data runs past the last
+    /// register of the bank nothing is written and an [InvalidLocation](Error::InvalidLocation)
+    /// error is returned.
+    pub fn write_from_register_blocking(
+        &mut self,
+        bank: u8,
+        start_register: u8,
+        data: &[u8],
+    ) -> Result<(), Error<I2cError>> {
+        check_register_run(bank, start_register, data.len())?;
+        self.bank_blocking(bank)?;
+        for (i, chunk) in data.chunks(self.max_chunk).enumerate() {
+            let register = start_register + (i * self.max_chunk) as u8;
+            self.count_transaction();
+            self.i2c.transaction(
+                self.address,
+                &mut [Operation::Write(&[register]), Operation::Write(chunk)],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Turn blinking on with the `blink_period` timing code from 0 to 7, giving a period of
+    /// 0.27 s times 2 to the power of the code, or off with `None`. Only the LEDs whose blink bit
+    /// is set (see [set_blink_leds](Self::set_blink_leds_blocking)) blink. If `frame0_intensity`
+    /// is set every frame is shown with the brightness of frame 0. A code above 7 returns an
+    /// [InvalidTiming](Error::InvalidTiming) error.
+    pub fn set_display_options_blocking(
+        &mut self,
+        frame0_intensity: bool,
+        blink_period: Option<u8>,
+    ) -> Result<(), Error<I2cError>> {
+        let value = display_options(frame0_intensity, blink_period)?;
+        self.write_register_blocking(addresses::CONFIG_BANK, addresses::BLINK, value)?;
+        Ok(())
+    }
+
+    /// Set the gain of the audio input used by audio sync and audio play, from 0 (0 dB) up to 7
+    /// (21 dB) in 3 dB steps, with `agc` choosing whether the chip adjusts it automatically.
+    pub fn set_audio_gain_blocking(&mut self, gain: u8, agc: Agc) -> Result<(), I2cError> {
+        self.write_register_blocking(
+            addresses::CONFIG_BANK,
+            addresses::GAIN,
+            gain_control(gain, agc),
+        )
+    }
+
+    /// Set the slave device to sync audio
+    pub fn audio_sync_blocking(&mut self, yes: bool) -> Result<(), I2cError> {
+        self.write_register_blocking(
+            addresses::CONFIG_BANK,
+            addresses::AUDIOSYNC,
+            if yes { addresses::AUDIOSYNC_ENABLE } else { 0 },
+        )?;
+        Ok(())
+    }
+
+    /// Set the device to sleep
+    pub fn sleep_blocking(&mut self, yes: bool) -> Result<(), I2cError> {
+        self.write_register_blocking(
+            addresses::CONFIG_BANK,
+            addresses::SHUTDOWN,
+            if yes { 0 } else { 1 },
+        )?;
+        Ok(())
+    }
+
+    fn write_register_blocking(
+        &mut self,
+        bank: u8,
+        register: u8,
+        value: u8,
+    ) -> Result<(), I2cError> {
+        self.bank_blocking(bank)?;
+        self.count_transaction();
+        self.i2c.write(self.address, &[register, value])?;
+        Ok(())
+    }
+
+    fn read_register_blocking(&mut self, bank: u8, register: u8) -> Result<u8, I2cError> {
+        self.bank_blocking(bank)?;
+        let mut buf = [0];
+        self.count_transaction();
+        self.i2c.write_read(self.address, &[register], &mut buf)?;
+        Ok(buf[0])
+    }
+
+    /// Setup the chip clearing the first `frames` frames and enabling the LEDs of `enables` in
+    /// them, which lets a board enable only its wired LEDs without a second enable write.
+    pub(crate) fn setup_with_enables_blocking(
+        &mut self,
+        delay: &mut impl DelayNs,
+        frames: u8,
+        enables: [u8; 18],
+    ) -> Result<(), Error<I2cError>> {
+        self.current_bank = None;
+        self.sleep_blocking(true)?;
+        delay.delay_ms(RESET_MS as u32);
+        self.mode_blocking(addresses::PICTURE_MODE)?;
+        self.frame_blocking(0)?;
+        for frame in 0..frames {
+            self.fill_raw_blocking(0, BlinkSetting::Off, frame)?;
+            for col in 0..18 {
+                self.write_register_blocking(
+                    frame,
+                    addresses::ENABLE_OFFSET + col,
+                    enables[col as usize],
+                )?;
+            }
+        }
+        self.enable_state = enables;
+        self.audio_sync_blocking(false)?;
+        self.sleep_blocking(false)?;
+        Ok(())
+    }
+
+    fn write_enable_state_blocking(&mut self, first: usize, last: usize) -> Result<(), I2cError> {
+        self.bank_blocking(self.frame)?;
+        let (payload, len) =
+            state_payload(addresses::ENABLE_OFFSET, &self.enable_state, first, last);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload[..len])?;
+        Ok(())
+    }
+
+    /// Set every LED of `frame` to `value` as it is, without inversion, and apply `blink`.
+    fn fill_raw_blocking(
+        &mut self,
+        value: u8,
+        blink: BlinkSetting,
+        frame: u8,
+    ) -> Result<(), I2cError> {
+        self.bank_blocking(frame)?;
+        for row in 0..6 {
+            let payload = register_run::<25>(addresses::COLOR_OFFSET + row * 24, value);
+            self.count_transaction();
+            self.i2c.write(self.address, &payload)?;
+        }
+        if let Some(data) = blink.register_value() {
+            for col in 0..18 {
+                self.write_register_blocking(frame, addresses::BLINK_OFFSET + col, data)?;
+            }
+            if frame == self.frame {
+                self.blink_state = [data; 18];
+            }
+        }
+        Ok(())
+    }
+
+    fn write_pixels_blocking(&mut self, frame: u8, first: u8, buf: &[u8]) -> Result<(), I2cError> {
+        self.bank_blocking(frame)?;
+        let mut inverted = [0; LED_COUNT];
+        let buf = invert_pixels(self.inverted, buf, &mut inverted);
+        for (i, chunk) in buf.chunks(self.max_chunk).enumerate() {
+            let register = addresses::COLOR_OFFSET + first + (i * self.max_chunk) as u8;
+            // Adjacent write operations are sent back to back without a restart, so the register
+            // address can prefix the data without copying it into a larger buffer.
+            self.count_transaction();
+            self.i2c.transaction(
+                self.address,
+                &mut [Operation::Write(&[register]), Operation::Write(chunk)],
+            )?;
+        }
+        Ok(())
+    }
+
+    fn read_pixels_blocking(&mut self, frame: u8) -> Result<[u8; LED_COUNT], I2cError> {
+        self.bank_blocking(frame)?;
+        let mut buf = [0; LED_COUNT];
+        self.count_transaction();
+        self.i2c
+            .write_read(self.address, &[addresses::COLOR_OFFSET], &mut buf)?;
+        if self.inverted {
+            buf.iter_mut().for_each(|value| *value = 255 - *value);
+        }
+        Ok(buf)
+    }
+
+    /// Select `bank` unless it is already selected. The bank select is always its own write and
+    /// can't share an i2c transaction with the data that follows: adjacent writes in a transaction
+    /// are sent without a restart, so the chip would take the data as more bytes for the registers
+    /// after the bank register instead of writing them into the selected bank.
+    fn bank_blocking(&mut self, bank: u8) -> Result<(), I2cError> {
+        if self.current_bank == Some(bank) {
+            return Ok(());
+        }
+        self.count_transaction();
+        self.i2c
+            .write(self.address, &[addresses::BANK_ADDRESS, bank])?;
+        self.current_bank = Some(bank);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, I2cError> IS31FL3731<I2C>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = I2cError>,
+{
+    /// Try each of the standard [ADDRESSES] in order by selecting the configuration
+    /// bank, returning the first one that acknowledges. This is helpful if you don't know how the
+    /// AD pin of your board is strapped. The returned address can then be passed to
+    /// [new](Self::new).
+    pub async fn probe_address(i2c: &mut I2C) -> Option<u8> {
+        for address in ADDRESSES {
+            if i2c
+                .write(address, &[addresses::BANK_ADDRESS, addresses::CONFIG_BANK])
+                .await
+                .is_ok()
+            {
+                return Some(address);
+            }
+        }
+        None
+    }
+
+    /// Fill the display with a single brightness. The brightness should range from 0 to 255. The
+    /// blink setting turns blinking on or off for every LED in the frame, or with
+    /// [Leave](BlinkSetting::Leave) keeps the blink registers as they are. Those registers are
+    /// undefined until the frame is cleared or blink is set, so use `On` or `Off` on a fresh frame.
+    /// An `Option<bool>` is still accepted, `None` being `Leave`. The
+    /// frame is the frame in which the fill should be applied to. Please consult the "General
+    /// Description" section on the first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
+    /// for more information on frames. Filling a frame never changes which frame is displayed.
+    /// LEDs that are disabled stay off since the chip masks them, but they keep the new
+    /// brightness and show it if they are enabled later.
+    pub async fn fill(
+        &mut self,
+        brightness: u8,
+        blink: impl Into<BlinkSetting>,
+        frame: u8,
+    ) -> Result<(), I2cError> {
+        let brightness = self.apply_inversion(brightness);
+        self.fill_raw(brightness, blink.into(), frame).await
+    }
+
+    /// Prepare `frame` by setting every LED in it to `brightness`, without changing which frame
+    /// is displayed or the driver's current frame. The frame must be less than 8, otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub async fn fill_into(&mut self, frame: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.fill(brightness, BlinkSetting::Leave, frame).await?;
+        Ok(())
+    }
+
+    /// Set every LED of all 8 frames to `brightness`, e.g. to start an autoplay movie from a
+    /// known state. Like [fill_into](Self::fill_into) neither the displayed frame nor the driver's
+    /// current frame are changed.
+    pub async fn fill_all_frames(&mut self, brightness: u8) -> Result<(), I2cError> {
+        for frame in 0..8 {
+            self.fill(brightness, BlinkSetting::Leave, frame).await?;
+        }
+        Ok(())
+    }
+
+    /// Set every LED in the current frame to `brightness` and then display that frame.
+    pub async fn fill_current_and_show(&mut self, brightness: u8) -> Result<(), Error<I2cError>> {
+        self.fill(brightness, BlinkSetting::Leave, self.frame)
+            .await?;
+        self.display_frame(self.frame).await
+    }
+
+    /// Fill the current frame with `brightness` on the LEDs that are enabled, setting the
+    /// disabled ones to 0 so they stay dark even if they are enabled later. This uses the
+    /// driver's cached enable state from [set_led_enabled](Self::set_led_enabled) and friends.
+    pub async fn fill_enabled_only(&mut self, brightness: u8) -> Result<(), Error<I2cError>> {
+        let frame = enabled_only(&self.enable_state, brightness);
+        self.all_pixels(&frame).await
+    }
+
+    /// Like [fill](Self::fill) but also able to enable every LED of the frame first. The
+    /// enable registers are written before the brightness and blink so that the fill is visible
+    /// even on a chip that hasn't been [setup](Self::setup). When `enable_all` is false the
+    /// enable registers are left untouched.
+    pub async fn fill_full(
+        &mut self,
+        brightness: u8,
+        blink: bool,
+        enable_all: bool,
+        frame: u8,
+    ) -> Result<(), I2cError> {
+        if enable_all {
+            self.bank(frame).await?;
+            let payload = register_run::<19>(addresses::ENABLE_OFFSET, 0xFF);
+            self.count_transaction();
+            self.i2c.write(self.address, &payload).await?;
+            if frame == self.frame {
+                self.enable_state = [0xFF; 18];
+            }
+        }
+        self.fill(brightness, Some(blink), frame).await
+    }
+
+    /// Turn blinking on or off for every LED of `frame` without touching its brightness, so blink
+    /// can be toggled on an already drawn frame. The frame must be less than 8, otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub async fn frame_blink_all(&mut self, frame: u8, blink: bool) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.bank(frame).await?;
+        let data = if blink { 0xFF } else { 0 };
+        let payload = register_run::<19>(addresses::BLINK_OFFSET, data);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload).await?;
+        if frame == self.frame {
+            self.blink_state = [data; 18];
+        }
+        Ok(())
+    }
+
+    /// Clear the display by turning every LED of the current frame off. Unlike a
+    /// [fill](Self::fill) with a brightness of 0 this ignores
+    /// [inversion](Self::set_inverted), so the LEDs are dark either way.
+    pub async fn clear(&mut self) -> Result<(), I2cError> {
+        self.fill_raw(0, BlinkSetting::Leave, self.frame).await
+    }
+
+    /// Set every LED of the current frame to `brightness` as fast as possible. Only the 6
+    /// brightness row writes are sent, the blink registers are never touched.
+    pub async fn clear_to(&mut self, brightness: u8) -> Result<(), I2cError> {
+        self.fill(brightness, BlinkSetting::Leave, self.frame).await
+    }
+
+    /// Setup the display. Should be called before interacting with the device to ensure proper
+    /// functionality. Delay is something that your device's HAL should provide which allows for
+    /// the process to sleep for a certain amount of time (in this case 10 MS to perform a reset).
+    ///
+    /// When you run this function the following steps will occur:
+    /// 1. The chip will be told that it's being "reset".
+    /// 2. All frames will be cleared.
+    /// 3. Audio syncing will be turned off.
+    /// 4. The chip will be told that it's being turned back on.
+    pub async fn setup(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        self.setup_frames(delay, 8).await
+    }
+
+    /// Like [setup](Self::setup) but only clears and enables the first `frames` frames.
+    /// Boards that only ever draw to frame 0 can pass 1 here to cut the i2c traffic of setup by
+    /// roughly 8 times. If `frames` is more than 8 then an [InvalidFrame](Error::InvalidFrame)
+    /// error is returned.
+    pub async fn setup_frames(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        frames: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if frames > 8 {
+            return Err(Error::InvalidFrame(frames));
+        }
+        self.setup_with_enables(delay, frames, [0xFF; 18]).await
+    }
+
+    /// Like [setup](Self::setup) but with `preserve_enables` set the LEDs are enabled as they
+    /// last were through this driver, e.g. with [set_led_enabled](Self::set_led_enabled), instead
+    /// of all being enabled. This lets a sparse matrix be set up again after an error without
+    /// turning its unpopulated LEDs back on. A new driver hasn't enabled any LEDs yet, so
+    /// preserving before the first setup leaves every LED disabled.
+    pub async fn setup_ex(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        preserve_enables: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let enables = if preserve_enables {
+            self.enable_state
+        } else {
+            [0xFF; 18]
+        };
+        self.setup_with_enables(delay, 8, enables).await
+    }
+
+    /// Run [setup](Self::setup) and then fade `target` in on frame 0 over roughly `ms`
+    /// milliseconds instead of snapping it on. The chip is taken out of shutdown by the setup
+    /// before the fade starts so the whole ramp is visible.
+    pub async fn setup_and_fade_in(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        target: &[u8; LED_COUNT],
+        ms: u16,
+    ) -> Result<(), Error<I2cError>> {
+        self.setup(delay).await?;
+        for step in 1..=FADE_STEPS {
+            self.all_pixels(&scale_frame(target, step, FADE_STEPS))
+                .await?;
+            delay.delay_ms((ms / FADE_STEPS) as u32).await;
+        }
+        Ok(())
+    }
+
+    /// Fade the current frame out to black over roughly `ms` milliseconds, the counterpart to
+    /// [setup_and_fade_in](Self::setup_and_fade_in). The frame is read back from the chip once
+    /// before the fade starts, so no copy of it needs to be kept. If `sleep` is true the chip is
+    /// put into shutdown once the frame is dark.
+    pub async fn fade_out(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        ms: u16,
+        sleep: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let start = self.read_all_pixels().await?;
+        for step in (0..FADE_STEPS).rev() {
+            self.all_pixels(&scale_frame(&start, step, FADE_STEPS))
+                .await?;
+            delay.delay_ms((ms / FADE_STEPS) as u32).await;
+        }
+        if sleep {
+            self.sleep(true).await?;
+        }
+        Ok(())
+    }
+
+    /// Pulse the current frame by dimming it to black and back along a smooth, roughly sinusoidal
+    /// curve, each cycle taking about `period_ms` milliseconds. Unlike the chip's breath feature
+    /// this keeps the pattern of the frame, every LED is scaled relative to its own brightness.
+    /// The frame is read back from the chip once before the first cycle and is left as it was
+    /// when the last cycle ends. The pulse repeats `cycles` times, or forever if `cycles` is 0.
+    pub async fn breathe_current(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        period_ms: u16,
+        cycles: u8,
+    ) -> Result<(), Error<I2cError>> {
+        let start = self.read_all_pixels().await?;
+        let step_ms = period_ms as u32 / (2 * FADE_STEPS as u32);
+        let mut breathed = 0;
+        while cycles == 0 || breathed < cycles {
+            for step in 1..=2 * FADE_STEPS {
+                self.all_pixels(&scale_frame(&start, breath_level(step), 255))
+                    .await?;
+                delay.delay_ms(step_ms).await;
+            }
+            breathed = breathed.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// Set the brightness for a specific LED. Just like the [fill method](Self::fill) the
+    /// brightness should range from 0 to 255. If the LED is out of range then the function will
+    /// return an error of [InvalidLocation](Error::InvalidLocation).
+    pub async fn pixel(&mut self, led: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
+        }
+        let brightness = self.apply_inversion(brightness);
+        self.write_register(self.frame, addresses::COLOR_OFFSET + led, brightness)
+            .await?;
+        Ok(())
+    }
+
+    /// Like [pixel](Self::pixel) but writes to `frame` instead of the current frame, without
+    /// changing the driver's current frame or which frame is displayed. This is the per LED
+    /// counterpart to [fill](Self::fill) with a frame and is handy for drawing a hidden frame. The
+    /// frame must be less than 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is
+    /// returned.
+    pub async fn pixel_in_frame(
+        &mut self,
+        frame: u8,
+        led: u8,
+        brightness: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
+        }
+        let brightness = self.apply_inversion(brightness);
+        self.write_register(frame, addresses::COLOR_OFFSET + led, brightness)
+            .await?;
+        Ok(())
+    }
+
+    /// Read the brightness of a specific LED in the current frame, replace it with the result of
+    /// `f` and write it back. This is handy for simple effects like fading an LED towards zero
+    /// without keeping a buffer, but costs a read and a write on the bus for every call. If the
+    /// LED is out of range then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub async fn update_pixel(
+        &mut self,
+        led: u8,
+        f: impl Fn(u8) -> u8,
+    ) -> Result<(), Error<I2cError>> {
+        if led >= LED_COUNT as u8 {
+            return Err(Error::InvalidLocation(led));
+        }
+        let brightness = self
+            .read_register(self.frame, addresses::COLOR_OFFSET + led)
+            .await?;
+        self.pixel(led, f(self.apply_inversion(brightness))).await
+    }
+
+    /// Set the brightness of `buf.len()` consecutive LEDs starting at `first` in a single write.
+    /// If the run goes past the last LED an [InvalidLocation](Error::InvalidLocation) error is
+    /// returned.
+    pub async fn pixels(&mut self, first: u8, buf: &[u8]) -> Result<(), Error<I2cError>> {
+        if first as usize + buf.len() > LED_COUNT {
+            return Err(Error::InvalidLocation(first));
+        }
+        if buf.is_empty() {
+            return Ok(());
+        }
+        self.write_pixels(self.frame, first, buf).await?;
+        Ok(())
+    }
+
+    /// Write a sparse set of `(led, brightness)` changes to the current frame. Runs of changes to
+    /// consecutive LEDs are coalesced into a single write each, so sort the changes by LED to get
+    /// the fewest writes. Every LED is checked before anything is written, returning an
+    /// [InvalidLocation](Error::InvalidLocation) error if one is out of range.
+    pub async fn apply_delta(&mut self, changes: &[(u8, u8)]) -> Result<(), Error<I2cError>> {
+        check_delta(changes)?;
+        let mut run = [0; LED_COUNT];
+        let mut rest = changes;
+        while !rest.is_empty() {
+            let (first, len) = delta_run(rest, &mut run);
+            self.write_pixels(self.frame, first, &run[..len]).await?;
+            rest = &rest[len..];
+        }
+        Ok(())
+    }
+
+    /// Individially assign and updated brightness values for all 144 LEDs at once.
+    pub async fn all_pixels(&mut self, buf: &[u8; LED_COUNT]) -> Result<(), Error<I2cError>> {
+        self.write_pixels(self.frame, 0, buf).await?;
+        Ok(())
+    }
+
+    /// Like [all_pixels](Self::all_pixels) but for a buffer whose size is only known at
+    /// runtime. If `buf` isn't exactly 144 bytes long nothing is written and an
+    /// [InvalidLocation](Error::InvalidLocation) error with the length (capped at 255) is
+    /// returned.
+    pub async fn all_pixels_slice(&mut self, buf: &[u8]) -> Result<(), Error<I2cError>> {
+        if buf.len() != LED_COUNT {
+            return Err(Error::InvalidLocation(buf.len().min(255) as u8));
+        }
+        self.write_pixels(self.frame, 0, buf).await?;
+        Ok(())
+    }
+
+    /// Like [all_pixels](Self::all_pixels) but the brightness values come from an iterator, e.g.
+    /// `(0..144).map(|i| (i * 7) as u8)`, so no buffer needs to be built up front. Only the first
+    /// 144 values are used, the rest are never pulled from the iterator. If it ends early the
+    /// remaining LEDs are set to 0.
+    pub async fn all_pixels_from(
+        &mut self,
+        values: impl IntoIterator<Item = u8>,
+    ) -> Result<(), Error<I2cError>> {
+        let mut buf = [0; LED_COUNT];
+        for (led, value) in buf.iter_mut().zip(values) {
+            *led = value;
+        }
+        self.all_pixels(&buf).await
+    }
+
+    /// Decode a run-length encoded frame and write it to the current frame in a single write.
+    /// `rle` is a list of `(count, value)` byte pairs, each setting the next `count` LEDs to
+    /// `value`, and the runs must add up to exactly 144 LEDs. This lets animations be stored in
+    /// flash in far less space than 144 bytes a frame. If the data is malformed nothing is
+    /// written and an [InvalidLocation](Error::InvalidLocation) error with the number of LEDs
+    /// decoded (capped at 255) is returned.
+    pub async fn load_rle_frame(&mut self, rle: &[u8]) -> Result<(), Error<I2cError>> {
+        self.all_pixels(&decode_rle(rle)?).await
+    }
+
+    /// Play a precomputed animation on the current frame by writing each of `frames` in turn with
+    /// [all_pixels](Self::all_pixels), waiting `1000 / fps` milliseconds between them. The
+    /// sequence is repeated `loops` times, or forever if `loops` is 0. An `fps` of 0 is treated
+    /// as 1.
+    pub async fn play_frames(
+        &mut self,
+        frames: &[[u8; LED_COUNT]],
+        fps: u8,
+        loops: u8,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        let frame_ms = 1000 / fps.max(1) as u32;
+        let mut played = 0;
+        while loops == 0 || played < loops {
+            for frame in frames {
+                self.all_pixels(frame).await?;
+                delay.delay_ms(frame_ms).await;
+            }
+            played = played.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// Like [play_frames](Self::play_frames) but the frames come from an iterator, so they can be
+    /// generated lazily instead of being stored up front. Playback ends when the iterator does.
+    /// Each frame is written before waiting, so dropping the future while it waits (for example
+    /// when another branch of a `select!` finishes first) stops the animation on a complete
+    /// frame.
+    pub async fn play_frames_iter(
+        &mut self,
+        frames: impl IntoIterator<Item = [u8; LED_COUNT]>,
+        fps: u8,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        let frame_ms = 1000 / fps.max(1) as u32;
+        for frame in frames {
+            self.all_pixels(&frame).await?;
+            delay.delay_ms(frame_ms).await;
+        }
+        Ok(())
+    }
+
+    /// Read back the brightness values of all 144 LEDs in the current frame. This is the read
+    /// counterpart to [all_pixels](Self::all_pixels) and is done as a single transfer.
+    pub async fn read_all_pixels(&mut self) -> Result<[u8; LED_COUNT], I2cError> {
+        self.read_pixels(self.frame).await
+    }
+
+    /// Scale the brightness of every LED in the current frame by `numerator / denominator`,
+    /// saturating at 255. Useful for dimming whatever is currently displayed (e.g. a fade-out)
+    /// without keeping a copy of it. Note that this reads all 144 PWM registers back from the chip
+    /// before writing them again, so it costs roughly twice the bus traffic of
+    /// [all_pixels](Self::all_pixels). A `denominator` of 0 returns an
+    /// [InvalidScale](Error::InvalidScale) error before anything is read.
+    pub async fn scale_current(
+        &mut self,
+        numerator: u8,
+        denominator: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if denominator == 0 {
+            return Err(Error::InvalidScale(denominator));
+        }
+        let buf = self.read_all_pixels().await?;
+        self.all_pixels(&scale_frame(&buf, numerator as u16, denominator as u16))
+            .await
+    }
+
+    /// Write brightness values for all 144 LEDs into `frame` without changing which frame is
+    /// displayed or the driver's current frame. Selecting a frame's bank only decides which frame
+    /// the data is written to, the displayed picture is controlled by [frame](Self::frame).
+    /// This makes it possible to draw into a hidden frame and switch to it once it's complete.
+    /// The frame must be less than 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is
+    /// returned.
+    pub async fn render_into(
+        &mut self,
+        frame: u8,
+        buf: &[u8; LED_COUNT],
+    ) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.write_pixels(frame, 0, buf).await?;
+        Ok(())
+    }
+
+    /// Write all 8 frames at once, `frames[n]` going to frame `n`, which is the usual way to
+    /// load an [autoplay](Self::autoplay) movie. Each frame is sent in a single write and, like
+    /// [render_into](Self::render_into), neither the displayed frame nor the driver's current
+    /// frame are changed.
+    pub async fn write_all_frames(
+        &mut self,
+        frames: &[[u8; LED_COUNT]; 8],
+    ) -> Result<(), I2cError> {
+        for (frame, buf) in (0..).zip(frames) {
+            self.write_pixels(frame, 0, buf).await?;
+        }
+        Ok(())
+    }
+
+    /// Copy the brightness values of every LED in frame `src` into frame `dst`. Both frames must
+    /// be less than 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is returned. Neither
+    /// the displayed frame nor the current frame are changed, which makes this useful for
+    /// preparing a hidden frame before switching to it.
+    pub async fn copy_frame(&mut self, src: u8, dst: u8) -> Result<(), Error<I2cError>> {
+        if src >= 8 {
+            return Err(Error::InvalidFrame(src));
+        }
+        if dst >= 8 {
+            return Err(Error::InvalidFrame(dst));
+        }
+        let buf = self.read_pixels(src).await?;
+        self.write_pixels(dst, 0, &buf).await?;
+        Ok(())
+    }
+
+    /// Enable or disable a single LED in the current frame. A disabled LED stays off regardless of
+    /// its brightness. If the LED is out of range then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub async fn set_led_enabled(&mut self, led: u8, enabled: bool) -> Result<(), Error<I2cError>> {
+        self.enable_region(led, 1, enabled).await
+    }
+
+    /// Enable or disable `len` consecutive LEDs starting at `start` in the current frame with a
+    /// single write. This is handy for matrices where only part of the 144 LEDs are populated. If
+    /// the region doesn't fit within the 144 LEDs then the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub async fn enable_region(
+        &mut self,
+        start: u8,
+        len: u8,
+        enabled: bool,
+    ) -> Result<(), Error<I2cError>> {
+        if let Some((first, last)) = self.update_enable_region(start, len, enabled)? {
+            self.write_enable_state(first, last).await?;
+        }
+        Ok(())
+    }
+
+    /// Replace the enable state of every LED in the current frame at once. Each byte of `mask`
+    /// covers 8 LEDs with bit 0 being the lowest LED index.
+    pub async fn set_enable_mask(&mut self, mask: &[u8; 18]) -> Result<(), I2cError> {
+        self.enable_state = *mask;
+        self.write_enable_state(0, 17).await
+    }
+
+    /// Enable or disable every LED in the current frame with a single write. Disabling blanks
+    /// the display while keeping the brightness of every LED, so enabling them again brings the
+    /// picture straight back. Unlike [sleep](Self::sleep) the chip stays running.
+    pub async fn enable_all(&mut self, enabled: bool) -> Result<(), I2cError> {
+        self.set_enable_mask(&[if enabled { 0xFF } else { 0 }; 18])
+            .await
+    }
+
+    /// Turn blinking on or off for the listed LEDs of the current frame, leaving the blink of
+    /// every other LED as it was. The driver keeps a copy of the blink registers so only the
+    /// bytes covering the listed LEDs are written, in a single write. Blinking only shows once it
+    /// is enabled in the chip's display option register. If any LED is out of range then nothing
+    /// is written and the function will return an error of
+    /// [InvalidLocation](Error::InvalidLocation).
+    pub async fn set_blink_leds(
+        &mut self,
+        leds: &[u8],
+        blink: bool,
+    ) -> Result<(), Error<I2cError>> {
+        let Some((first, last)) = self.update_blink_state(leds, blink)? else {
+            return Ok(());
+        };
+        self.bank(self.frame).await?;
+        let (payload, len) = state_payload(addresses::BLINK_OFFSET, &self.blink_state, first, last);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload[..len]).await?;
+        Ok(())
+    }
+
+    /// Set frame ranging from 0 to 8. Please consult the "General Description" section on the
+    /// first page of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
+    /// for more information on frames.
+    pub async fn frame(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
+        if frame > 8 {
+            return Err(Error::InvalidLocation(frame));
+        }
+        self.frame = frame;
+        self.write_register(addresses::CONFIG_BANK, addresses::FRAME, frame)
+            .await?;
+        Ok(())
+    }
+
+    /// Read the frame register back from the chip and update the driver's cached frame to match.
+    /// Useful after a watchdog or brownout has reset the chip without the driver noticing, which
+    /// would otherwise leave pixel writes going to the wrong frame.
+    pub async fn resync_frame(&mut self) -> Result<(), I2cError> {
+        self.current_bank = None;
+        let frame = self
+            .read_register(addresses::CONFIG_BANK, addresses::FRAME)
+            .await?;
+        self.frame = frame & addresses::FRAME_MASK;
+        Ok(())
+    }
+
+    /// Set the 24 LEDs of one of the chip's 6 hardware rows in the current frame to `brightness`,
+    /// using raw LED indices rather than any device mapping. This is meant for checking the
+    /// wiring of a freshly assembled board one row at a time. If the row is 6 or more an
+    /// [InvalidLocation](Error::InvalidLocation) error is returned.
+    pub async fn light_row(&mut self, chip_row: u8, brightness: u8) -> Result<(), Error<I2cError>> {
+        if chip_row >= 6 {
+            return Err(Error::InvalidLocation(chip_row));
+        }
+        self.pixels(chip_row * 24, &[brightness; 24]).await
+    }
+
+    /// Run a simple test pattern on the current frame to verify wiring after assembling a board.
+    /// Each LED is lit on its own in index order, then the whole frame is filled, then cleared.
+    /// `step_ms` is how long each step is shown for.
+    pub async fn self_test(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        step_ms: u32,
+    ) -> Result<(), Error<I2cError>> {
+        for led in 0..LED_COUNT as u8 {
+            self.pixel(led, 0xFF).await?;
+            delay.delay_ms(step_ms).await;
+            self.pixel(led, 0).await?;
+        }
+        self.fill(0xFF, BlinkSetting::Leave, self.frame).await?;
+        delay.delay_ms(step_ms).await;
+        self.clear().await?;
+        Ok(())
+    }
+
+    /// Show `frame` on the display without changing the driver's current frame, so drawing
+    /// methods keep writing to the frame they were writing to before. The frame must be less than
+    /// 8, otherwise an [InvalidFrame](Error::InvalidFrame) error is returned.
+    pub async fn display_frame(&mut self, frame: u8) -> Result<(), Error<I2cError>> {
+        if frame >= 8 {
+            return Err(Error::InvalidFrame(frame));
+        }
+        self.write_register(addresses::CONFIG_BANK, addresses::FRAME, frame)
+            .await?;
+        Ok(())
+    }
+
+    /// Send a reset message to the slave device. Delay is something that your device's HAL should
+    /// provide which allows for the process to sleep for a certain amount of time (in this case 10
+    /// MS to perform a reset). The display stays dark afterwards until [setup](Self::setup) is
+    /// called again, or use [reset_and_setup](Self::reset_and_setup) to do both.
+    pub async fn reset(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), I2cError> {
+        self.reset_with_delay(delay, RESET_MS).await
+    }
+
+    /// Like [reset](Self::reset) but holds the chip in shutdown for `reset_ms` milliseconds
+    /// instead of [RESET_MS]. Some parts need around 20 ms to settle, while a shorter time can
+    /// speed up boot on parts that don't.
+    pub async fn reset_with_delay(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        reset_ms: u16,
+    ) -> Result<(), I2cError> {
+        self.current_bank = None;
+        self.sleep(true).await?;
+        delay.delay_ms(reset_ms as u32).await;
+        self.sleep(false).await?;
+        Ok(())
+    }
+
+    /// Reset the device and run the full [setup](Self::setup), leaving it ready to draw.
+    pub async fn reset_and_setup(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+    ) -> Result<(), Error<I2cError>> {
+        self.reset(delay).await?;
+        self.setup(delay).await
+    }
+
+    /// Set the device mode. Please consult page 17 and 18 of the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
     /// to learn mode about the different modes.
     pub async fn mode(&mut self, mode: u8) -> Result<(), I2cError> {
         self.write_register(addresses::CONFIG_BANK, addresses::MODE_REGISTER, mode)
@@ -302,12 +1992,276 @@ where
         Ok(())
     }
 
+    /// Play frames as a movie. The chip shows `frames` frames (1 to 8) beginning with `start`,
+    /// each for `delay_ms` milliseconds rounded down to the chip's 11 ms steps (from 11 up to
+    /// 704), and plays the movie `loops` times (up to 7) or forever if `loops` is 0. An
+    /// [InvalidFrame](Error::InvalidFrame) error is returned if `start` or `frames` is out of
+    /// range.
+    pub async fn autoplay(
+        &mut self,
+        start: u8,
+        frames: u8,
+        loops: u8,
+        delay_ms: u16,
+    ) -> Result<(), Error<I2cError>> {
+        check_movie(start, frames)?;
+        let (control1, control2) = autoplay_control(frames, loops, delay_ms);
+        self.write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY1, control1)
+            .await?;
+        self.write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2, control2)
+            .await?;
+        let mode = addresses::AUTOPLAY_MODE | start;
+        self.autoplay_mode = Some(mode);
+        self.paused_frame = None;
+        self.mode(mode).await?;
+        Ok(())
+    }
+
+    /// Like [autoplay](Self::autoplay) but with each frame fading in and out using the breath
+    /// feature, for a cross fading slideshow. `fade_in` and `fade_out` are the chip's timing
+    /// codes from 0 to 7, giving a fade of 26 ms times 2 to the power of the code. A code above 7
+    /// returns an [InvalidTiming](Error::InvalidTiming) error. Everything is checked before
+    /// anything is written.
+    pub async fn autoplay_with_breath(
+        &mut self,
+        start: u8,
+        frames: u8,
+        loops: u8,
+        delay_ms: u16,
+        fade_in: u8,
+        fade_out: u8,
+    ) -> Result<(), Error<I2cError>> {
+        check_movie(start, frames)?;
+        let (breath1, breath2) = breath_control(fade_in, fade_out)?;
+        self.write_register(addresses::CONFIG_BANK, addresses::BREATH1, breath1)
+            .await?;
+        self.write_register(addresses::CONFIG_BANK, addresses::BREATH2, breath2)
+            .await?;
+        self.autoplay(start, frames, loops, delay_ms).await
+    }
+
+    /// Freeze an autoplay movie on the frame it is currently showing by switching to picture
+    /// mode. The frame becomes the current frame of the driver, and the autoplay settings stay in
+    /// the chip so [autoplay_resume](Self::autoplay_resume) can continue the movie from it
+    /// without reprogramming them.
+    pub async fn autoplay_pause(&mut self) -> Result<(), I2cError> {
+        let state = self
+            .read_register(addresses::CONFIG_BANK, addresses::FRAME_STATE)
+            .await?;
+        let frame = state & addresses::FRAME_STATE_FRAME_MASK;
+        self.write_register(addresses::CONFIG_BANK, addresses::FRAME, frame)
+            .await?;
+        self.mode(addresses::PICTURE_MODE).await?;
+        self.frame = frame;
+        self.paused_frame = Some(frame);
+        Ok(())
+    }
+
+    /// Switch back to autoplay mode after pausing it using the settings of the last
+    /// [autoplay](Self::autoplay) call. Only the mode register is written. A paused movie
+    /// continues from the frame it was paused on, otherwise it starts again from its first
+    /// frame. Nothing is written if no movie has been set up with [autoplay](Self::autoplay).
+    pub async fn autoplay_resume(&mut self) -> Result<(), I2cError> {
+        if let Some(mode) = self.resume_mode() {
+            self.mode(mode).await?;
+            self.paused_frame = None;
+        }
+        Ok(())
+    }
+
+    /// Switch to audio play mode, where the chip picks which of the frames from `start_frame` to
+    /// `end_frame` to show from the amplitude of the audio input. An
+    /// [InvalidFrame](Error::InvalidFrame) error is returned unless
+    /// `start_frame <= end_frame < 8`. The frame window shares a register with the autoplay
+    /// settings, so call [autoplay](Self::autoplay) again before going back to autoplay.
+    pub async fn audio_play_setup(
+        &mut self,
+        start_frame: u8,
+        end_frame: u8,
+    ) -> Result<(), Error<I2cError>> {
+        if end_frame >= 8 {
+            return Err(Error::InvalidFrame(end_frame));
+        }
+        if start_frame > end_frame {
+            return Err(Error::InvalidFrame(start_frame));
+        }
+        let (control1, _) = autoplay_control(end_frame - start_frame + 1, 0, 0);
+        self.write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY1, control1)
+            .await?;
+        self.mode(addresses::AUDIOPLAY_MODE | start_frame).await?;
+        Ok(())
+    }
+
+    /// Switch the chip to `mode`, writing any settings the mode needs before the mode register
+    /// so the display never shows a half configured state.
+    pub async fn set_display_mode(&mut self, mode: DisplayMode) -> Result<(), Error<I2cError>> {
+        match mode {
+            DisplayMode::Picture { frame } => {
+                self.display_frame(frame).await?;
+                self.mode(addresses::PICTURE_MODE).await?;
+            }
+            DisplayMode::Autoplay {
+                start,
+                frames,
+                loops,
+                delay_ms,
+            } => self.autoplay(start, frames, loops, delay_ms).await?,
+            DisplayMode::AudioPlay => self.mode(addresses::AUDIOPLAY_MODE).await?,
+        }
+        Ok(())
+    }
+
+    /// Read the frame register and assert that it matches the driver's cached frame, catching
+    /// desyncs (e.g. after a [raw_write](Self::raw_write)) early. This only does anything in
+    /// builds with debug assertions enabled and is a no-op in release builds.
+    pub async fn verify_frame(&mut self) -> Result<(), I2cError> {
+        #[cfg(debug_assertions)]
+        {
+            let frame = self
+                .read_register(addresses::CONFIG_BANK, addresses::FRAME)
+                .await?;
+            debug_assert_eq!(
+                frame & addresses::FRAME_MASK,
+                self.frame,
+                "frame register doesn't match the cached frame"
+            );
+        }
+        Ok(())
+    }
+
+    /// Check whether an autoplay movie has finished all of its loops since this was last called.
+    /// The interrupt bit in the frame state register is cleared by the chip when it's read, so
+    /// polling this between loops can be used to refill frames for long scripted animations.
+    pub async fn autoplay_loop_finished(&mut self) -> Result<bool, I2cError> {
+        let state = self
+            .read_register(addresses::CONFIG_BANK, addresses::FRAME_STATE)
+            .await?;
+        Ok(state & addresses::FRAME_STATE_INT != 0)
+    }
+
+    /// Read back the shutdown, mode and frame state of the chip, which helps find out why a
+    /// display stays dark. Reading the frame state clears the movie finished flag on the chip,
+    /// like [autoplay_loop_finished](Self::autoplay_loop_finished) does.
+    pub async fn status(&mut self) -> Result<ChipStatus, I2cError> {
+        let shutdown = self
+            .read_register(addresses::CONFIG_BANK, addresses::SHUTDOWN)
+            .await?;
+        let mode = self
+            .read_register(addresses::CONFIG_BANK, addresses::MODE_REGISTER)
+            .await?;
+        let state = self
+            .read_register(addresses::CONFIG_BANK, addresses::FRAME_STATE)
+            .await?;
+        Ok(ChipStatus {
+            shutdown: shutdown & addresses::SHUTDOWN_NORMAL == 0,
+            mode: mode & addresses::MODE_MASK,
+            displayed_frame: state & addresses::FRAME_STATE_FRAME_MASK,
+            movie_finished: state & addresses::FRAME_STATE_INT != 0,
+        })
+    }
+
+    /// Check that the chip at the address behaves like an IS31FL3731 by writing two test
+    /// patterns to the autoplay delay register and reading each one back, restoring the original
+    /// value afterwards. That register only matters while a movie is playing, whereas writing the
+    /// frame register would switch the displayed frame, so the display is left alone. If a
+    /// pattern doesn't come back an [UnexpectedReadback](Error::UnexpectedReadback) error with
+    /// the value read is returned, which usually means a different chip such as an IS31FL3733
+    /// with another register map. The original value is still written back in that case.
+    pub async fn sanity_check(&mut self) -> Result<(), Error<I2cError>> {
+        let original = self
+            .read_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2)
+            .await?;
+        for pattern in [0x2A, 0x15] {
+            self.write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2, pattern)
+                .await?;
+            let value = self
+                .read_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2)
+                .await?;
+            if value != pattern {
+                // The readback error is the one worth reporting, so a failed restore is ignored.
+                let _ = self
+                    .write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2, original)
+                    .await;
+                return Err(Error::UnexpectedReadback(value));
+            }
+        }
+        self.write_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2, original)
+            .await?;
+        Ok(())
+    }
+
+    /// Write `value` to any `register` of any `bank`, taking care of selecting the bank first.
+    /// This is an escape hatch for registers that don't have a dedicated method. Note that it
+    /// bypasses the state cached by the driver (such as the current frame or the LED enable
+    /// state), so writing to registers the driver also manages can leave that state stale.
+    pub async fn raw_write(&mut self, bank: u8, register: u8, value: u8) -> Result<(), I2cError> {
+        self.write_register(bank, register, value).await
+    }
+
+    /// Write `data` to consecutive registers of `bank` starting at `start_register`. The chip
+    /// moves on to the next register after every byte it receives, which is how
+    /// [all_pixels](Self::all_pixels) sends all 144 brightness values at once, and this makes the
+    /// same bulk write available for any region such as the enable or blink registers. Like
+    /// [raw_write](Self::raw_write) it bypasses the state cached by the driver and brightness
+    /// values aren't inverted. The bank must be a frame below 8 or the
+    /// [configuration bank](addresses::CONFIG_BANK), otherwise an
+    /// [InvalidFrame](Error::InvalidFrame) error is returned. If the data runs past the last
+    /// register of the bank nothing is written and an [InvalidLocation](Error::InvalidLocation)
+    /// error is returned.
+    pub async fn write_from_register(
+        &mut self,
+        bank: u8,
+        start_register: u8,
+        data: &[u8],
+    ) -> Result<(), Error<I2cError>> {
+        check_register_run(bank, start_register, data.len())?;
+        self.bank(bank).await?;
+        for (i, chunk) in data.chunks(self.max_chunk).enumerate() {
+            let register = start_register + (i * self.max_chunk) as u8;
+            self.count_transaction();
+            self.i2c
+                .transaction(
+                    self.address,
+                    &mut [Operation::Write(&[register]), Operation::Write(chunk)],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Turn blinking on with the `blink_period` timing code from 0 to 7, giving a period of
+    /// 0.27 s times 2 to the power of the code, or off with `None`. Only the LEDs whose blink bit
+    /// is set (see [set_blink_leds](Self::set_blink_leds)) blink. If `frame0_intensity` is set
+    /// every frame is shown with the brightness of frame 0. A code above 7 returns an
+    /// [InvalidTiming](Error::InvalidTiming) error.
+    pub async fn set_display_options(
+        &mut self,
+        frame0_intensity: bool,
+        blink_period: Option<u8>,
+    ) -> Result<(), Error<I2cError>> {
+        let value = display_options(frame0_intensity, blink_period)?;
+        self.write_register(addresses::CONFIG_BANK, addresses::BLINK, value)
+            .await?;
+        Ok(())
+    }
+
+    /// Set the gain of the audio input used by audio sync and audio play, from 0 (0 dB) up to 7
+    /// (21 dB) in 3 dB steps, with `agc` choosing whether the chip adjusts it automatically.
+    pub async fn set_audio_gain(&mut self, gain: u8, agc: Agc) -> Result<(), I2cError> {
+        self.write_register(
+            addresses::CONFIG_BANK,
+            addresses::GAIN,
+            gain_control(gain, agc),
+        )
+        .await
+    }
+
     /// Set the slave device to sync audio
     pub async fn audio_sync(&mut self, yes: bool) -> Result<(), I2cError> {
         self.write_register(
             addresses::CONFIG_BANK,
             addresses::AUDIOSYNC,
-            if yes { 1 } else { 0 },
+            if yes { addresses::AUDIOSYNC_ENABLE } else { 0 },
         )
         .await?;
         Ok(())
@@ -326,14 +2280,122 @@ where
 
     async fn write_register(&mut self, bank: u8, register: u8, value: u8) -> Result<(), I2cError> {
         self.bank(bank).await?;
+        self.count_transaction();
         self.i2c.write(self.address, &[register, value]).await?;
         Ok(())
     }
 
+    async fn setup_with_enables(
+        &mut self,
+        delay: &mut impl embedded_hal_async::delay::DelayNs,
+        frames: u8,
+        enables: [u8; 18],
+    ) -> Result<(), Error<I2cError>> {
+        self.current_bank = None;
+        self.sleep(true).await?;
+        delay.delay_ms(RESET_MS as u32).await;
+        self.mode(addresses::PICTURE_MODE).await?;
+        self.frame(0).await?;
+        for frame in 0..frames {
+            self.fill_raw(0, BlinkSetting::Off, frame).await?;
+            for col in 0..18 {
+                self.write_register(frame, addresses::ENABLE_OFFSET + col, enables[col as usize])
+                    .await?;
+            }
+        }
+        self.enable_state = enables;
+        self.audio_sync(false).await?;
+        self.sleep(false).await?;
+        Ok(())
+    }
+
+    async fn write_enable_state(&mut self, first: usize, last: usize) -> Result<(), I2cError> {
+        self.bank(self.frame).await?;
+        let (payload, len) =
+            state_payload(addresses::ENABLE_OFFSET, &self.enable_state, first, last);
+        self.count_transaction();
+        self.i2c.write(self.address, &payload[..len]).await?;
+        Ok(())
+    }
+
+    /// Set every LED of `frame` to `value` as it is, without inversion, and apply `blink`.
+    async fn fill_raw(
+        &mut self,
+        value: u8,
+        blink: BlinkSetting,
+        frame: u8,
+    ) -> Result<(), I2cError> {
+        self.bank(frame).await?;
+        for row in 0..6 {
+            let payload = register_run::<25>(addresses::COLOR_OFFSET + row * 24, value);
+            self.count_transaction();
+            self.i2c.write(self.address, &payload).await?;
+        }
+        if let Some(data) = blink.register_value() {
+            for col in 0..18 {
+                self.write_register(frame, addresses::BLINK_OFFSET + col, data)
+                    .await?;
+            }
+            if frame == self.frame {
+                self.blink_state = [data; 18];
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_pixels(&mut self, frame: u8, first: u8, buf: &[u8]) -> Result<(), I2cError> {
+        self.bank(frame).await?;
+        let mut inverted = [0; LED_COUNT];
+        let buf = invert_pixels(self.inverted, buf, &mut inverted);
+        for (i, chunk) in buf.chunks(self.max_chunk).enumerate() {
+            let register = addresses::COLOR_OFFSET + first + (i * self.max_chunk) as u8;
+            // Adjacent write operations are sent back to back without a restart, so the register
+            // address can prefix the data without copying it into a larger buffer.
+            self.count_transaction();
+            self.i2c
+                .transaction(
+                    self.address,
+                    &mut [Operation::Write(&[register]), Operation::Write(chunk)],
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn read_register(&mut self, bank: u8, register: u8) -> Result<u8, I2cError> {
+        self.bank(bank).await?;
+        let mut buf = [0];
+        self.count_transaction();
+        self.i2c
+            .write_read(self.address, &[register], &mut buf)
+            .await?;
+        Ok(buf[0])
+    }
+
+    async fn read_pixels(&mut self, frame: u8) -> Result<[u8; LED_COUNT], I2cError> {
+        self.bank(frame).await?;
+        let mut buf = [0; LED_COUNT];
+        self.count_transaction();
+        self.i2c
+            .write_read(self.address, &[addresses::COLOR_OFFSET], &mut buf)
+            .await?;
+        if self.inverted {
+            buf.iter_mut().for_each(|value| *value = 255 - *value);
+        }
+        Ok(buf)
+    }
+
+    /// Select `bank` unless it is already selected, see [bank](Self::bank_blocking) for why this
+    /// is a separate write.
     async fn bank(&mut self, bank: u8) -> Result<(), I2cError> {
+        if self.current_bank == Some(bank) {
+            return Ok(());
+        }
+        self.count_transaction();
         self.i2c
             .write(self.address, &[addresses::BANK_ADDRESS, bank])
             .await?;
+        self.current_bank = Some(bank);
         Ok(())
     }
 }
@@ -356,8 +2418,285 @@ pub fn gamma(val: u8) -> u8 {
     GAMMA_TABLE[val as usize]
 }
 
+/// The `i`-th of `steps` values linearly interpolated from `from` to `to`, using only integer
+/// math. Step 0 gives `from` and step `steps` gives `to`, with `i` past `steps` clamped. A
+/// `steps` of 0 gives `to`. Useful for fading with [fill](IS31FL3731::fill_blocking) or
+/// [pixel](IS31FL3731::pixel_blocking) without a frame buffer.
+pub fn ramp(from: u8, to: u8, steps: u8, i: u8) -> u8 {
+    if steps == 0 {
+        return to;
+    }
+    let i = i.min(steps) as i32;
+    let (from, to) = (from as i32, to as i32);
+    (from + (to - from) * i / steps as i32) as u8
+}
+
+/// Convert a hue, saturation and value color to red, green and blue. All components range from 0
+/// to 255, with the hue wrapping around the color wheel.
+pub fn hsv_to_rgb(h: u8, s: u8, v: u8) -> (u8, u8, u8) {
+    if s == 0 {
+        return (v, v, v);
+    }
+    let region = h / 43;
+    let remainder = (h - region * 43) as u16 * 6;
+    let (s, v) = (s as u16, v as u16);
+    let p = (v * (255 - s) / 256) as u8;
+    let q = (v * (255 - s * remainder / 256) / 256) as u8;
+    let t = (v * (255 - s * (255 - remainder) / 256) / 256) as u8;
+    let v = v as u8;
+    match region {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    }
+}
+
+/// Gamma correct every value of `src` into `dst`. Only the first `min(src.len(), dst.len())`
+/// values are converted.
+pub fn gamma_slice(src: &[u8], dst: &mut [u8]) {
+    for (out, val) in dst.iter_mut().zip(src) {
+        *out = gamma(*val);
+    }
+}
+
+/// Gamma correct every value of `buf` in place.
+pub fn gamma_in_place(buf: &mut [u8]) {
+    for val in buf.iter_mut() {
+        *val = gamma(*val);
+    }
+}
+
+/// Gamma correction table for the red channel of an RGB LED, the same curve as [gamma].
+#[cfg(feature = "channel_gamma")]
+pub const GAMMA_R: [u8; 256] = GAMMA_TABLE;
+/// Gamma correction table for the green channel of an RGB LED. Green LEDs are usually much
+/// brighter than red ones at the same current so the curve is scaled down for white balance.
+#[cfg(feature = "channel_gamma")]
+pub const GAMMA_G: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1,
+    1, 1, 2, 2, 2, 2, 2, 2, 2, 3, 3, 3, 3, 3, 4, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 7, 8,
+    8, 8, 8, 9, 9, 9, 10, 10, 10, 11, 11, 11, 12, 12, 12, 13, 13, 14, 14, 14, 15, 15, 16, 16, 16,
+    17, 17, 18, 18, 19, 19, 20, 20, 20, 21, 21, 22, 22, 23, 23, 24, 24, 25, 26, 26, 27, 27, 28, 28,
+    29, 29, 30, 31, 31, 32, 32, 33, 34, 34, 35, 36, 36, 37, 37, 38, 39, 39, 40, 41, 42, 42, 43, 44,
+    44, 45, 46, 47, 47, 48, 49, 50, 50, 51, 52, 53, 53, 54, 55, 56, 57, 58, 58, 59, 60, 61, 62, 63,
+    63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85,
+    86, 87, 88, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 102, 103, 104, 105, 106, 107, 108,
+    110, 111, 112, 113, 114, 116, 117, 118, 119, 120, 122, 123, 124, 125, 127, 128, 129, 131, 132,
+    133, 134, 136, 137, 138, 140, 141, 142, 144, 145, 147, 148, 149, 151, 152, 154, 155, 156, 158,
+    159, 161, 162, 164, 165, 167, 168, 169, 171, 172, 174, 176,
+];
+/// Gamma correction table for the blue channel of an RGB LED, scaled down slightly for white
+/// balance.
+#[cfg(feature = "channel_gamma")]
+pub const GAMMA_B: [u8; 256] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 2, 2, 2,
+    2, 2, 2, 3, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 6, 6, 6, 6, 7, 7, 7, 8, 8, 8, 9, 9, 9, 10, 10,
+    11, 11, 11, 12, 12, 13, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18, 19, 19, 20, 20, 21, 21,
+    22, 23, 23, 24, 24, 25, 26, 26, 27, 27, 28, 29, 29, 30, 31, 31, 32, 33, 34, 34, 35, 36, 36, 37,
+    38, 39, 40, 40, 41, 42, 43, 44, 44, 45, 46, 47, 48, 49, 50, 50, 51, 52, 53, 54, 55, 56, 57, 58,
+    59, 60, 61, 62, 63, 64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 78, 79, 80, 81, 82, 83,
+    84, 86, 87, 88, 89, 90, 92, 93, 94, 95, 97, 98, 99, 100, 102, 103, 104, 106, 107, 108, 110,
+    111, 112, 114, 115, 117, 118, 119, 121, 122, 124, 125, 127, 128, 130, 131, 133, 134, 136, 137,
+    139, 140, 142, 143, 145, 146, 148, 150, 151, 153, 154, 156, 158, 159, 161, 163, 164, 166, 168,
+    169, 171, 173, 175, 176, 178, 180, 182, 184, 185, 187, 189, 191, 193, 194, 196, 198, 200, 202,
+    204, 206, 208, 210, 211, 213, 215, 217, 219, 221, 223, 225, 227, 229, 231, 233, 235, 237, 240,
+];
+
+/// Number of steps used by the fade methods.
+const FADE_STEPS: u16 = 32;
+
+/// Scale every value of `buf` by `numerator / denominator`, saturating at 255.
+fn scale_frame(buf: &[u8; LED_COUNT], numerator: u16, denominator: u16) -> [u8; LED_COUNT] {
+    let mut scaled = [0; LED_COUNT];
+    for (out, value) in scaled.iter_mut().zip(buf) {
+        *out = (*value as u32 * numerator as u32 / denominator as u32).min(255) as u8;
+    }
+    scaled
+}
+
+/// Fail with the first LED of `changes` that is past the last LED of the chip, if any.
+fn check_delta<E>(changes: &[(u8, u8)]) -> Result<(), Error<E>> {
+    match changes.iter().find(|(led, _)| *led as usize >= LED_COUNT) {
+        Some(&(led, _)) => Err(Error::InvalidLocation(led)),
+        None => Ok(()),
+    }
+}
+
+/// Copy the brightness values of the run of consecutive LEDs at the start of `changes` into
+/// `run`, returning the first LED of the run and its length. `changes` must not be empty and must
+/// have passed [check_delta].
+fn delta_run(changes: &[(u8, u8)], run: &mut [u8; LED_COUNT]) -> (u8, usize) {
+    let first = changes[0].0;
+    let mut len = 0;
+    while len < changes.len() && changes[len].0 as usize == first as usize + len {
+        run[len] = changes[len].1;
+        len += 1;
+    }
+    (first, len)
+}
+
+/// Decode `(count, value)` pairs into a frame, failing unless they cover exactly every LED.
+fn decode_rle<E>(rle: &[u8]) -> Result<[u8; LED_COUNT], Error<E>> {
+    let mut frame = [0; LED_COUNT];
+    let mut decoded = 0;
+    for run in rle.chunks(2) {
+        let [count, value] = *run else {
+            return Err(Error::InvalidLocation(decoded.min(255) as u8));
+        };
+        let end = decoded + count as usize;
+        if end > LED_COUNT {
+            return Err(Error::InvalidLocation(end.min(255) as u8));
+        }
+        frame[decoded..end].fill(value);
+        decoded = end;
+    }
+    if decoded != LED_COUNT {
+        return Err(Error::InvalidLocation(decoded as u8));
+    }
+    Ok(frame)
+}
+
+/// Check that `len` registers starting at `start` are all within `bank`.
+fn check_register_run<E>(bank: u8, start: u8, len: usize) -> Result<(), Error<E>> {
+    let end = match bank {
+        0..=7 => addresses::COLOR_OFFSET as usize + LED_COUNT,
+        addresses::CONFIG_BANK => addresses::ADC as usize + 1,
+        _ => return Err(Error::InvalidFrame(bank)),
+    };
+    if start as usize + len > end {
+        return Err(Error::InvalidLocation(start));
+    }
+    Ok(())
+}
+
+/// The brightness out of 255 at `step` of a breath lasting `2 * FADE_STEPS` steps, from full at
+/// step 0 down to black halfway and back to full. A smoothstep curve stands in for a sine.
+fn breath_level(step: u16) -> u16 {
+    let n = FADE_STEPS as u32;
+    let t = (step as u32).abs_diff(n);
+    (255 * t * t * (3 * n - 2 * t) / (n * n * n)) as u16
+}
+
+// The payloads below are shared by the blocking and async methods so that both send the same
+// bytes.
+
+/// A write setting `N - 1` consecutive registers starting at `register` to `value`.
+fn register_run<const N: usize>(register: u8, value: u8) -> [u8; N] {
+    let mut payload = [value; N];
+    payload[0] = register;
+    payload
+}
+
+/// A write of the enable or blink registers starting at `offset`, covering bytes `first` to `last`
+/// of `state`, along with its length.
+fn state_payload(offset: u8, state: &[u8; 18], first: usize, last: usize) -> ([u8; 19], usize) {
+    let mut payload = [0; 19];
+    payload[0] = offset + first as u8;
+    let len = last - first + 1;
+    payload[1..=len].copy_from_slice(&state[first..=last]);
+    (payload, len + 1)
+}
+
+/// `buf` inverted into `scratch` when `inverted` is set, otherwise `buf` itself.
+fn invert_pixels<'a>(inverted: bool, buf: &'a [u8], scratch: &'a mut [u8; LED_COUNT]) -> &'a [u8] {
+    if !inverted {
+        return buf;
+    }
+    for (value, original) in scratch.iter_mut().zip(buf) {
+        *value = 255 - original;
+    }
+    &scratch[..buf.len()]
+}
+
+/// A frame with `brightness` on every LED enabled in `enable_state` and 0 on the rest.
+fn enabled_only(enable_state: &[u8; 18], brightness: u8) -> [u8; LED_COUNT] {
+    let mut frame = [0; LED_COUNT];
+    for (led, value) in frame.iter_mut().enumerate() {
+        if enable_state[led / 8] & (1 << (led % 8)) != 0 {
+            *value = brightness;
+        }
+    }
+    frame
+}
+
+/// Check the `start` frame and number of `frames` of an autoplay movie.
+fn check_movie<E>(start: u8, frames: u8) -> Result<(), Error<E>> {
+    if start >= 8 {
+        return Err(Error::InvalidFrame(start));
+    }
+    if frames == 0 || frames > 8 {
+        return Err(Error::InvalidFrame(frames));
+    }
+    Ok(())
+}
+
+/// The values of the two autoplay control registers for a movie of `frames` frames played
+/// `loops` times, showing each frame for `delay_ms`.
+fn autoplay_control(frames: u8, loops: u8, delay_ms: u16) -> (u8, u8) {
+    // 8 frames and 64 steps of 11 ms are both encoded as 0.
+    let steps = (delay_ms / 11).clamp(1, 64) as u8;
+    (
+        ((loops.min(7) << addresses::AUTOPLAY1_LOOPS_SHIFT) & addresses::AUTOPLAY1_LOOPS_MASK)
+            | (frames & addresses::AUTOPLAY1_FRAMES_MASK),
+        steps & addresses::AUTOPLAY2_DELAY_MASK,
+    )
+}
+
+/// The values of the two breath control registers for fades using the `fade_in` and `fade_out`
+/// timing codes, with breathing enabled.
+fn breath_control<E>(fade_in: u8, fade_out: u8) -> Result<(u8, u8), Error<E>> {
+    if fade_in > 7 {
+        return Err(Error::InvalidTiming(fade_in));
+    }
+    if fade_out > 7 {
+        return Err(Error::InvalidTiming(fade_out));
+    }
+    // The extinguish time in the second register is left at 0.
+    Ok((
+        (fade_out << addresses::BREATH1_FADE_OUT_SHIFT) | fade_in,
+        addresses::BREATH2_ENABLE,
+    ))
+}
+
+/// The value of the display option register for blinking with the `blink_period` timing code,
+/// or no blinking for `None`, taking the brightness of every frame from frame 0 if
+/// `frame0_intensity` is set.
+fn display_options<E>(frame0_intensity: bool, blink_period: Option<u8>) -> Result<u8, Error<E>> {
+    let mut value = if frame0_intensity {
+        addresses::BLINK_INTENSITY_FRAME0
+    } else {
+        0
+    };
+    if let Some(period) = blink_period {
+        if period > addresses::BLINK_PERIOD_MASK {
+            return Err(Error::InvalidTiming(period));
+        }
+        value |= addresses::BLINK_DISPLAY_ENABLE | period;
+    }
+    Ok(value)
+}
+
+/// The value of the audio gain register for `gain` (clamped to 7) and the `agc` mode.
+fn gain_control(gain: u8, agc: Agc) -> u8 {
+    let agc = match agc {
+        Agc::Off => 0,
+        Agc::Slow => addresses::GAIN_AGC_ENABLE,
+        Agc::Fast => addresses::GAIN_AGC_ENABLE | addresses::GAIN_AGC_FAST,
+    };
+    agc | gain.min(addresses::GAIN_MASK)
+}
+
 pub const DEFAULT_ADDRESS: u8 = 0x74;
+/// Every address the IS31FL3731 can be strapped to using its AD pin.
+pub const ADDRESSES: [u8; 4] = [0x74, 0x75, 0x76, 0x77];
 pub const LED_COUNT: usize = 144;
+/// The milliseconds the chip is held in shutdown by [reset](IS31FL3731::reset_blocking) and
+/// [setup](IS31FL3731::setup_blocking). The data sheet doesn't give a minimum for this, use
+/// [reset_with_delay](IS31FL3731::reset_with_delay_blocking) for parts that need longer.
+pub const RESET_MS: u16 = 10;
 
 /// See the [data sheet](https://www.lumissil.com/assets/pdf/core/IS31FL3731_DS.pdf)
 /// for more information on registers.
@@ -368,12 +2707,56 @@ pub mod addresses {
     pub const AUTOPLAY2: u8 = 0x03;
     pub const BLINK: u8 = 0x05;
     pub const AUDIOSYNC: u8 = 0x06;
+    pub const FRAME_STATE: u8 = 0x07;
     pub const BREATH1: u8 = 0x08;
     pub const BREATH2: u8 = 0x09;
     pub const SHUTDOWN: u8 = 0x0A;
     pub const GAIN: u8 = 0x0B;
     pub const ADC: u8 = 0x0C;
 
+    /// The mode bits of the [MODE_REGISTER], one of the modes below.
+    pub const MODE_MASK: u8 = 0x18;
+    /// The bits of the [MODE_REGISTER] holding the frame an autoplay movie starts on.
+    pub const MODE_START_FRAME_MASK: u8 = 0x07;
+    /// The bits of the [FRAME] register holding the displayed frame.
+    pub const FRAME_MASK: u8 = 0x07;
+    /// The position of the loop count in [AUTOPLAY1], 0 meaning endless.
+    pub const AUTOPLAY1_LOOPS_SHIFT: u8 = 4;
+    /// The bits of [AUTOPLAY1] holding the loop count.
+    pub const AUTOPLAY1_LOOPS_MASK: u8 = 0x70;
+    /// The bits of [AUTOPLAY1] holding the number of frames, 0 meaning all 8.
+    pub const AUTOPLAY1_FRAMES_MASK: u8 = 0x07;
+    /// The bits of [AUTOPLAY2] holding the frame delay in steps of 11 ms, 0 meaning 64 steps.
+    pub const AUTOPLAY2_DELAY_MASK: u8 = 0x3F;
+    /// Set in the [BLINK] register to use the brightness of frame 0 for every frame.
+    pub const BLINK_INTENSITY_FRAME0: u8 = 0x20;
+    /// Set in the [BLINK] register to enable blinking.
+    pub const BLINK_DISPLAY_ENABLE: u8 = 0x08;
+    /// The bits of the [BLINK] register holding the blink period time.
+    pub const BLINK_PERIOD_MASK: u8 = 0x07;
+    /// Set in the [AUDIOSYNC] register to modulate the brightness with the audio input.
+    pub const AUDIOSYNC_ENABLE: u8 = 0x01;
+    /// Set in the [FRAME_STATE] register once an autoplay movie has finished, cleared on read.
+    pub const FRAME_STATE_INT: u8 = 0x10;
+    /// The bits of the [FRAME_STATE] register holding the frame being displayed.
+    pub const FRAME_STATE_FRAME_MASK: u8 = 0x07;
+    /// The position of the fade out time in [BREATH1].
+    pub const BREATH1_FADE_OUT_SHIFT: u8 = 4;
+    /// The bits of [BREATH1] holding the fade in time.
+    pub const BREATH1_FADE_IN_MASK: u8 = 0x07;
+    /// Set in [BREATH2] to enable breathing.
+    pub const BREATH2_ENABLE: u8 = 0x10;
+    /// The bits of [BREATH2] holding the extinguish time.
+    pub const BREATH2_EXTINGUISH_MASK: u8 = 0x07;
+    /// Set in the [SHUTDOWN] register for normal operation, clear for shutdown.
+    pub const SHUTDOWN_NORMAL: u8 = 0x01;
+    /// Set in the [GAIN] register for the fast automatic gain control mode.
+    pub const GAIN_AGC_FAST: u8 = 0x10;
+    /// Set in the [GAIN] register to enable automatic gain control of the audio input.
+    pub const GAIN_AGC_ENABLE: u8 = 0x08;
+    /// The bits of the [GAIN] register holding the audio gain.
+    pub const GAIN_MASK: u8 = 0x07;
+
     pub const CONFIG_BANK: u8 = 0x0B;
     pub const BANK_ADDRESS: u8 = 0xFD;
 
@@ -386,11 +2769,122 @@ pub mod addresses {
     pub const COLOR_OFFSET: u8 = 0x24;
 }
 
+/// What a [fill](IS31FL3731::fill_blocking) does to the blink of the LEDs it fills.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlinkSetting {
+    /// Keep the blink registers as they are. They hold no defined value until the frame has been
+    /// cleared or filled with `On` or `Off`, so a frame that was never cleared may blink at
+    /// random.
+    #[default]
+    Leave,
+    /// Make every LED blink.
+    On,
+    /// Stop every LED from blinking.
+    Off,
+}
+
+impl BlinkSetting {
+    /// The value written to every blink register, or `None` if they are left alone.
+    fn register_value(self) -> Option<u8> {
+        match self {
+            BlinkSetting::Leave => None,
+            BlinkSetting::On => Some(0xFF),
+            BlinkSetting::Off => Some(0),
+        }
+    }
+}
+
+impl From<Option<bool>> for BlinkSetting {
+    fn from(blink: Option<bool>) -> Self {
+        match blink {
+            None => BlinkSetting::Leave,
+            Some(true) => BlinkSetting::On,
+            Some(false) => BlinkSetting::Off,
+        }
+    }
+}
+
+/// What the chip shows, set with [set_display_mode](IS31FL3731::set_display_mode_blocking).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayMode {
+    /// Show a single frame.
+    Picture { frame: u8 },
+    /// Play frames as a movie, see [autoplay](IS31FL3731::autoplay_blocking) for the meaning of
+    /// each field.
+    Autoplay {
+        start: u8,
+        frames: u8,
+        loops: u8,
+        delay_ms: u16,
+    },
+    /// Pick the frame to show from the amplitude of the audio input.
+    AudioPlay,
+}
+
+/// The automatic gain control of the audio input, set with
+/// [set_audio_gain](IS31FL3731::set_audio_gain_blocking).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Agc {
+    /// Use the fixed gain only.
+    #[default]
+    Off,
+    /// Adjust the gain to the audio level in the slow mode.
+    Slow,
+    /// Adjust the gain to the audio level in the fast mode.
+    Fast,
+}
+
+/// The state of the chip read back by [status](IS31FL3731::status_blocking).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChipStatus {
+    /// Whether the chip is in software shutdown, which turns every LED off.
+    pub shutdown: bool,
+    /// The display mode, one of [PICTURE_MODE](addresses::PICTURE_MODE),
+    /// [AUTOPLAY_MODE](addresses::AUTOPLAY_MODE) or [AUDIOPLAY_MODE](addresses::AUDIOPLAY_MODE).
+    pub mode: u8,
+    /// The frame currently being displayed.
+    pub displayed_frame: u8,
+    /// Whether an autoplay movie has finished since the frame state was last read.
+    pub movie_finished: bool,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum Error<I2cError> {
     I2cError(I2cError),
     InvalidLocation(u8),
     InvalidFrame(u8),
+    InvalidTiming(u8),
+    InvalidAddress(u8),
+    UnexpectedReadback(u8),
+    InvalidScale(u8),
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::I2cError(error) => write!(f, "i2c error: {:?}", error),
+            Error::InvalidLocation(location) => write!(f, "invalid location: {}", location),
+            Error::InvalidFrame(frame) => write!(f, "invalid frame: {}", frame),
+            Error::InvalidTiming(code) => write!(f, "invalid timing code: {}", code),
+            Error::InvalidAddress(address) => write!(f, "invalid i2c address: {:#04x}", address),
+            Error::UnexpectedReadback(value) => {
+                write!(f, "unexpected value read back: {:#04x}", value)
+            }
+            Error::InvalidScale(denominator) => {
+                write!(f, "invalid scale denominator: {}", denominator)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: core::error::Error + 'static> core::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::I2cError(error) => Some(error),
+            _ => None,
+        }
+    }
 }
 
 impl<E> From<E> for Error<E> {
@@ -398,3 +2892,209 @@ impl<E> From<E> for Error<E> {
         Error::I2cError(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockI2c, NoDelay, Op};
+
+    #[test]
+    fn scale_current_rejects_a_zero_denominator() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        assert!(matches!(
+            device.scale_current_blocking(1, 0),
+            Err(Error::InvalidScale(0))
+        ));
+        assert!(device.i2c.transactions.is_empty());
+        device.fill_blocking(100, BlinkSetting::Leave, 0).unwrap();
+        device.scale_current_blocking(1, 2).unwrap();
+        assert_eq!(device.i2c.frame(0), [50; LED_COUNT]);
+    }
+
+    #[test]
+    fn autoplay_resumes_from_the_paused_frame() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        let mode = |device: &IS31FL3731<MockI2c>| {
+            device
+                .i2c
+                .register(addresses::CONFIG_BANK, addresses::MODE_REGISTER)
+        };
+        // Without a movie there is nothing to resume.
+        device.autoplay_resume_blocking().unwrap();
+        assert!(device.i2c.transactions.is_empty());
+
+        device.autoplay_blocking(1, 4, 0, 100).unwrap();
+        assert_eq!(mode(&device), addresses::AUTOPLAY_MODE | 1);
+        device
+            .i2c
+            .set_register(addresses::CONFIG_BANK, addresses::FRAME_STATE, 3);
+        device.autoplay_pause_blocking().unwrap();
+        assert_eq!(mode(&device), addresses::PICTURE_MODE);
+        assert_eq!(
+            device
+                .i2c
+                .register(addresses::CONFIG_BANK, addresses::FRAME),
+            3
+        );
+        assert_eq!(device.frame, 3);
+        device.autoplay_resume_blocking().unwrap();
+        assert_eq!(mode(&device), addresses::AUTOPLAY_MODE | 3);
+        // Once resumed the movie restarts from its first frame next time.
+        device.mode_blocking(addresses::PICTURE_MODE).unwrap();
+        device.autoplay_resume_blocking().unwrap();
+        assert_eq!(mode(&device), addresses::AUTOPLAY_MODE | 1);
+    }
+
+    #[test]
+    fn ramp_hits_endpoints() {
+        for (from, to) in [(0, 255), (255, 0), (10, 200), (7, 7)] {
+            for steps in [1, 3, 16, 255] {
+                assert_eq!(ramp(from, to, steps, 0), from);
+                assert_eq!(ramp(from, to, steps, steps), to);
+                assert_eq!(ramp(from, to, steps, 255), to);
+            }
+        }
+    }
+
+    #[test]
+    fn ramp_is_monotonic() {
+        for (from, to) in [(0, 255), (255, 0), (3, 250), (200, 100)] {
+            for steps in [1, 7, 32, 255] {
+                for i in 1..=steps {
+                    let (previous, current) =
+                        (ramp(from, to, steps, i - 1), ramp(from, to, steps, i));
+                    if to >= from {
+                        assert!(current >= previous);
+                    } else {
+                        assert!(current <= previous);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn ramp_with_no_steps_gives_to() {
+        assert_eq!(ramp(10, 200, 0, 0), 200);
+        assert_eq!(ramp(200, 10, 0, 5), 10);
+    }
+
+    #[test]
+    fn inverted_setup_and_clear_turn_leds_off() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        device.set_inverted(true);
+        device.setup_blocking(&mut NoDelay).unwrap();
+        for frame in 0..8 {
+            assert_eq!(device.i2c.frame(frame), [0; LED_COUNT]);
+        }
+        device.fill_blocking(10, BlinkSetting::Leave, 0).unwrap();
+        assert_eq!(device.i2c.frame(0), [245; LED_COUNT]);
+        device.clear_blocking().unwrap();
+        assert_eq!(device.i2c.frame(0), [0; LED_COUNT]);
+    }
+
+    #[test]
+    fn inverted_pixel_writes_match_fill() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        device.set_inverted(true);
+        device.fill_blocking(10, BlinkSetting::Leave, 0).unwrap();
+        device.pixel_blocking(3, 10).unwrap();
+        device.pixels_blocking(4, &[10, 10]).unwrap();
+        assert_eq!(device.i2c.frame(0), [245; LED_COUNT]);
+        device.all_pixels_blocking(&[20; LED_COUNT]).unwrap();
+        assert_eq!(device.i2c.frame(0), [235; LED_COUNT]);
+    }
+
+    #[test]
+    fn pixel_writes_are_grouped_per_chunk() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        device.set_max_chunk(100);
+        let buf: [u8; LED_COUNT] = core::array::from_fn(|led| led as u8);
+        device.all_pixels_blocking(&buf).unwrap();
+        device.all_pixels_blocking(&buf).unwrap();
+        let expected = [
+            [Op::Write([addresses::BANK_ADDRESS, 0].to_vec())].to_vec(),
+            [
+                Op::Write([addresses::COLOR_OFFSET].to_vec()),
+                Op::Write(buf[..100].to_vec()),
+            ]
+            .to_vec(),
+            [
+                Op::Write([addresses::COLOR_OFFSET + 100].to_vec()),
+                Op::Write(buf[100..].to_vec()),
+            ]
+            .to_vec(),
+        ];
+        // The bank is only selected for the first write, the second reuses it.
+        assert_eq!(device.i2c.transactions[..3], expected);
+        assert_eq!(device.i2c.transactions[3..], expected[1..]);
+        assert_eq!(device.i2c.frame(0), buf);
+    }
+
+    #[test]
+    fn option_fill_matches_blink_setting() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        let blink = |device: &IS31FL3731<MockI2c>| device.i2c.register(2, addresses::BLINK_OFFSET);
+        device.fill_blocking(10, Some(true), 2).unwrap();
+        assert_eq!(blink(&device), 0xFF);
+        device.fill_blocking(20, None, 2).unwrap();
+        assert_eq!(blink(&device), 0xFF);
+        device.fill_blocking(30, Some(false), 2).unwrap();
+        assert_eq!(blink(&device), 0);
+        assert_eq!(device.i2c.frame(2), [30; LED_COUNT]);
+    }
+
+    #[test]
+    fn sanity_check_restores_the_original_value() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        device
+            .i2c
+            .set_register(addresses::CONFIG_BANK, addresses::AUTOPLAY2, 7);
+        device.sanity_check_blocking().unwrap();
+        assert_eq!(
+            device
+                .i2c
+                .register(addresses::CONFIG_BANK, addresses::AUTOPLAY2),
+            7
+        );
+
+        device
+            .i2c
+            .make_read_only(addresses::CONFIG_BANK, addresses::AUTOPLAY2);
+        assert!(matches!(
+            device.sanity_check_blocking(),
+            Err(Error::UnexpectedReadback(7))
+        ));
+        assert_eq!(
+            device.i2c.transactions.last(),
+            Some(&[Op::Write([addresses::AUTOPLAY2, 7].to_vec())].to_vec())
+        );
+    }
+
+    #[test]
+    fn display_options_and_audio_gain_registers() {
+        let mut device = IS31FL3731::new(MockI2c::new(), 0x74);
+        let config = |device: &IS31FL3731<MockI2c>, register| {
+            device.i2c.register(addresses::CONFIG_BANK, register)
+        };
+        device.set_display_options_blocking(true, Some(3)).unwrap();
+        assert_eq!(
+            config(&device, addresses::BLINK),
+            addresses::BLINK_INTENSITY_FRAME0 | addresses::BLINK_DISPLAY_ENABLE | 3
+        );
+        device.set_display_options_blocking(false, None).unwrap();
+        assert_eq!(config(&device, addresses::BLINK), 0);
+        assert!(matches!(
+            device.set_display_options_blocking(false, Some(8)),
+            Err(Error::InvalidTiming(8))
+        ));
+
+        device.set_audio_gain_blocking(9, Agc::Fast).unwrap();
+        assert_eq!(
+            config(&device, addresses::GAIN),
+            addresses::GAIN_AGC_ENABLE | addresses::GAIN_AGC_FAST | 7
+        );
+        device.set_audio_gain_blocking(2, Agc::Off).unwrap();
+        assert_eq!(config(&device, addresses::GAIN), 2);
+    }
+}
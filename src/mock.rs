@@ -0,0 +1,117 @@
+extern crate std;
+
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+use std::vec::Vec;
+
+use crate::{addresses, LED_COUNT};
+
+/// A single operation of a recorded transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Op {
+    Write(Vec<u8>),
+    Read(usize),
+}
+
+/// An i2c bus that records every transaction and keeps the registers of a simulated chip, so
+/// tests can check both what went over the bus and what the chip ends up showing.
+pub(crate) struct MockI2c {
+    /// Every transaction in the order it was sent.
+    pub(crate) transactions: Vec<Vec<Op>>,
+    banks: Vec<[u8; 256]>,
+    bank: u8,
+    /// Registers that ignore writes, as `(bank, register)`.
+    read_only: Vec<(u8, u8)>,
+}
+
+impl MockI2c {
+    pub(crate) fn new() -> Self {
+        Self {
+            transactions: Vec::new(),
+            banks: std::vec![[0; 256]; addresses::CONFIG_BANK as usize + 1],
+            bank: 0,
+            read_only: Vec::new(),
+        }
+    }
+
+    /// The brightness of every LED of `frame` on the simulated chip.
+    pub(crate) fn frame(&self, frame: u8) -> [u8; LED_COUNT] {
+        let start = addresses::COLOR_OFFSET as usize;
+        self.banks[frame as usize][start..start + LED_COUNT]
+            .try_into()
+            .unwrap()
+    }
+
+    /// The value of `register` in `bank` on the simulated chip.
+    pub(crate) fn register(&self, bank: u8, register: u8) -> u8 {
+        self.banks[bank as usize][register as usize]
+    }
+
+    /// Set `register` in `bank` as the chip would, e.g. for registers the chip updates itself.
+    pub(crate) fn set_register(&mut self, bank: u8, register: u8, value: u8) {
+        self.banks[bank as usize][register as usize] = value;
+    }
+
+    /// Make `register` in `bank` ignore writes, like a chip with another register map might.
+    pub(crate) fn make_read_only(&mut self, bank: u8, register: u8) {
+        self.read_only.push((bank, register));
+    }
+
+    fn write(&mut self, register: u8, value: u8) {
+        if register == addresses::BANK_ADDRESS {
+            self.bank = value;
+        } else if !self.read_only.contains(&(self.bank, register)) {
+            self.banks[self.bank as usize][register as usize] = value;
+        }
+    }
+}
+
+impl ErrorType for MockI2c {
+    type Error = core::convert::Infallible;
+}
+
+impl I2c for MockI2c {
+    fn transaction(
+        &mut self,
+        _address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        // Writes within a transaction continue each other, with the first byte being the register
+        // that the rest of the bytes and any read auto increment from.
+        let mut register = None;
+        let mut recorded = Vec::new();
+        for operation in operations.iter_mut() {
+            match operation {
+                Operation::Write(bytes) => {
+                    recorded.push(Op::Write(bytes.to_vec()));
+                    for &byte in bytes.iter() {
+                        match register {
+                            None => register = Some(byte),
+                            Some(current) => {
+                                self.write(current, byte);
+                                register = Some(current.wrapping_add(1));
+                            }
+                        }
+                    }
+                }
+                Operation::Read(buf) => {
+                    recorded.push(Op::Read(buf.len()));
+                    let mut current = register.unwrap_or(0);
+                    for byte in buf.iter_mut() {
+                        *byte = self.banks[self.bank as usize][current as usize];
+                        current = current.wrapping_add(1);
+                    }
+                    register = Some(current);
+                }
+            }
+        }
+        self.transactions.push(recorded);
+        Ok(())
+    }
+}
+
+/// A delay that returns straight away.
+pub(crate) struct NoDelay;
+
+impl embedded_hal::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
@@ -0,0 +1,88 @@
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::{ErrorType, I2c, Operation, SevenBitAddress};
+
+/// An I2C bus wrapper that retries every failed transfer a fixed number of times with a delay in
+/// between. Useful on long cables where the chip occasionally fails to acknowledge. Usually
+/// created through [with_retries](crate::IS31FL3731::with_retries).
+pub struct RetryI2c<I2C, D> {
+    /// The wrapped i2c bus.
+    pub i2c: I2C,
+    delay: D,
+    attempts: u8,
+    retry_ms: u16,
+}
+
+impl<I2C, D> RetryI2c<I2C, D> {
+    /// Wrap `i2c` so that each transfer is attempted up to `attempts` times, waiting `retry_ms`
+    /// between attempts. An `attempts` of 0 is treated as 1.
+    pub fn new(i2c: I2C, delay: D, attempts: u8, retry_ms: u16) -> Self {
+        Self {
+            i2c,
+            delay,
+            attempts: attempts.max(1),
+            retry_ms,
+        }
+    }
+
+    /// Get back the wrapped i2c bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, D> RetryI2c<I2C, D>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    fn retry<T>(
+        &mut self,
+        mut op: impl FnMut(&mut I2C) -> Result<T, I2C::Error>,
+    ) -> Result<T, I2C::Error> {
+        let mut attempt = 1;
+        loop {
+            match op(&mut self.i2c) {
+                Err(_) if attempt < self.attempts => {
+                    attempt += 1;
+                    self.delay.delay_ms(self.retry_ms as u32);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl<I2C: ErrorType, D> ErrorType for RetryI2c<I2C, D> {
+    type Error = I2C::Error;
+}
+
+impl<I2C, D> I2c for RetryI2c<I2C, D>
+where
+    I2C: I2c,
+    D: DelayNs,
+{
+    fn read(&mut self, address: SevenBitAddress, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.retry(|i2c| i2c.read(address, read))
+    }
+
+    fn write(&mut self, address: SevenBitAddress, write: &[u8]) -> Result<(), Self::Error> {
+        self.retry(|i2c| i2c.write(address, write))
+    }
+
+    fn write_read(
+        &mut self,
+        address: SevenBitAddress,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.retry(|i2c| i2c.write_read(address, write, read))
+    }
+
+    fn transaction(
+        &mut self,
+        address: SevenBitAddress,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.retry(|i2c| i2c.transaction(address, operations))
+    }
+}
@@ -0,0 +1,48 @@
+use crate::IS31FL3731;
+use core::ops::{Deref, DerefMut};
+use embedded_hal::i2c::I2c;
+
+/// A device that is put to sleep when it goes out of scope, so a battery powered board doesn't
+/// keep drawing current after a panic or a clean teardown. Errors while shutting down are
+/// ignored since `Drop` can't return them. The wrapper dereferences to the device, so every
+/// method of [IS31FL3731] can be called on it directly. Usually created through
+/// [shutdown_on_drop](IS31FL3731::shutdown_on_drop).
+pub struct ShutdownOnDrop<I2C: I2c> {
+    device: Option<IS31FL3731<I2C>>,
+}
+
+impl<I2C: I2c> ShutdownOnDrop<I2C> {
+    /// Wrap `device` so that it is shut down when dropped.
+    pub fn new(device: IS31FL3731<I2C>) -> Self {
+        Self {
+            device: Some(device),
+        }
+    }
+
+    /// Get back the device without shutting it down.
+    pub fn release(mut self) -> IS31FL3731<I2C> {
+        self.device.take().unwrap()
+    }
+}
+
+impl<I2C: I2c> Deref for ShutdownOnDrop<I2C> {
+    type Target = IS31FL3731<I2C>;
+
+    fn deref(&self) -> &Self::Target {
+        self.device.as_ref().unwrap()
+    }
+}
+
+impl<I2C: I2c> DerefMut for ShutdownOnDrop<I2C> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.device.as_mut().unwrap()
+    }
+}
+
+impl<I2C: I2c> Drop for ShutdownOnDrop<I2C> {
+    fn drop(&mut self) {
+        if let Some(device) = self.device.as_mut() {
+            let _ = device.sleep_blocking(true);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+/// A small brightness image, such as an icon or a game character, that can be drawn anywhere on
+/// a board with the device wrappers' `draw_sprite_blocking`. The parts of the sprite that fall
+/// outside the board are clipped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sprite<'a> {
+    /// The width in pixels.
+    pub width: u8,
+    /// The height in pixels.
+    pub height: u8,
+    /// The brightness of each pixel, row by row from the top left.
+    pub data: &'a [u8],
+}
+
+impl<'a> Sprite<'a> {
+    /// Create a sprite from `data` laid out row by row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` doesn't hold exactly `width * height` values.
+    pub const fn new(width: u8, height: u8, data: &'a [u8]) -> Self {
+        assert!(data.len() == width as usize * height as usize);
+        Self {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// The brightness of the sprite at `(x, y)` within it, or `None` if that's outside it.
+    pub fn get(&self, x: u8, y: u8) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.data
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+    }
+
+    /// The `(x, y, brightness)` of every sprite pixel that lands on a board of `width` by
+    /// `height` when the sprite's top left corner is placed at `(x, y)`.
+    #[cfg(any(
+        feature = "charlie_bonnet",
+        feature = "charlie_wing",
+        feature = "eleven_7",
+        feature = "matrix",
+        feature = "scroll_phat_hd"
+    ))]
+    pub(crate) fn clipped(
+        &self,
+        x: i16,
+        y: i16,
+        (width, height): (u8, u8),
+    ) -> impl Iterator<Item = (u8, u8, u8)> + '_ {
+        (0..self.height).flat_map(move |sprite_y| {
+            (0..self.width).filter_map(move |sprite_x| {
+                let board_x = u8::try_from(x.checked_add(sprite_x as i16)?).ok()?;
+                let board_y = u8::try_from(y.checked_add(sprite_y as i16)?).ok()?;
+                if board_x >= width || board_y >= height {
+                    return None;
+                }
+                Some((board_x, board_y, self.get(sprite_x, sprite_y)?))
+            })
+        })
+    }
+}